@@ -90,13 +90,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // motor.start_path(0).await?;
     // 
     // // Wait for path to complete
-    // loop {
-    //     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    //     if motor.is_path_completed().await? {
-    //         println!("Path completed!");
-    //         break;
-    //     }
-    // }
+    // motor.wait_for_path_complete(std::time::Duration::from_millis(100), std::time::Duration::from_secs(30)).await?;
+    // println!("Path completed!");
     
     // Get motion status
     println!("\nChecking motion status...");
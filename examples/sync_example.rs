@@ -58,13 +58,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // motor.start_path(0)?;
     // 
     // // Poll for completion (blocking)
-    // loop {
-    //     std::thread::sleep(std::time::Duration::from_millis(100));
-    //     if motor.is_path_completed()? {
-    //         println!("Path completed!");
-    //         break;
-    //     }
-    // }
+    // motor.wait_for_path_complete(std::time::Duration::from_millis(100), std::time::Duration::from_secs(30))?;
+    // println!("Path completed!");
     
     // Get motion status
     println!("\nChecking motion status...");
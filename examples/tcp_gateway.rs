@@ -0,0 +1,47 @@
+/// Example driving motors behind an RS485-to-Ethernet gateway over Modbus TCP
+///
+/// The gateway forwards each TCP request to its RS485 bus, addressing the
+/// drive given by the Modbus unit ID. With auto reselect enabled, each
+/// `Em2rsClient` selects its slave ID before every request, so one TCP
+/// connection serves every drive on the bus exactly like a shared serial port.
+///
+/// Run with `cargo run --example tcp_gateway --features tcp`.
+use em2rs::{Em2rsClient, PathConfig, StepperConfig};
+use tokio_modbus::prelude::*;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("EM2RS Modbus TCP Gateway Example");
+    println!("================================");
+
+    // Connect ONCE to the gateway; the unit ID is switched per drive
+    let gateway = "192.168.1.100:502".parse()?;
+    println!("Connecting to gateway at {}", gateway);
+    let ctx = tcp::connect_slave(gateway, Slave::from(1)).await?;
+
+    // Motor 1 (slave ID 1)
+    let config1 = StepperConfig::new(1, 10000).with_phase_current(2.0);
+    let mut motor1 = Em2rsClient::new(ctx, config1).with_auto_reselect(true);
+    motor1.init().await?;
+    println!("Motor 1 version: {}", motor1.get_version().await?);
+
+    let path = PathConfig::new(0)?.with_position(5000).with_velocity(300);
+    motor1.apply_path_config(&path).await?;
+
+    // Motor 2 (slave ID 2) reuses the same TCP connection
+    let ctx = motor1.into_context();
+    let config2 = StepperConfig::new(2, 10000).with_phase_current(1.5);
+    let mut motor2 = Em2rsClient::new(ctx, config2).with_auto_reselect(true);
+    motor2.init().await?;
+    println!("Motor 2 version: {}", motor2.get_version().await?);
+
+    let status = motor2.get_motion_status().await?;
+    println!(
+        "Motor 2 Status - Enabled: {}, Running: {}, Fault: {}",
+        status.is_enabled(),
+        status.is_running(),
+        status.is_fault()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,2171 @@
+//! Client operations shared by the async and sync clients
+//!
+//! Each bus operation is written once, as an async method of [`ClientCore`]
+//! generic over the [`Backend`]. `client_api!` then exposes it on
+//! [`Em2rsClient`], awaited, and on [`Em2rsSyncClient`], run to completion
+//! with [`block_on`].
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_modbus::prelude::*;
+use crate::backend::{block_on, Backend};
+use crate::client::Em2rsClient;
+use crate::common;
+use crate::registers;
+use crate::registers::flags;
+use crate::sync::Em2rsSyncClient;
+use crate::types::*;
+
+/// Interval between motion status reads while waiting on the motor
+pub(crate) const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Connection and settings of a client, whichever its backend
+pub(crate) struct ClientCore<B> {
+    pub(crate) ctx: B,
+    pub(crate) slave_id: u8,
+    pub(crate) config: StepperConfig,
+    pub(crate) strict: bool,
+    pub(crate) auto_reselect: bool,
+    pub(crate) verify_init: bool,
+    pub(crate) path_layout: PathLayout,
+    pub(crate) timer: Option<Arc<dyn TransactionTimer>>,
+    pub(crate) command_settle: Duration,
+    /// Delay after each modbus request, see [`recommended_inter_frame_delay`]
+    pub(crate) request_delay: Option<Duration>,
+}
+
+impl<B: Backend> ClientCore<B> {
+    pub(crate) fn new(ctx: B, config: StepperConfig) -> Self {
+        Self {
+            ctx,
+            slave_id: config.slave_id,
+            request_delay: common::default_request_delay(config.baud_rate),
+            config,
+            strict: false,
+            auto_reselect: false,
+            verify_init: false,
+            path_layout: PathLayout::default(),
+            timer: None,
+            command_settle: Duration::ZERO,
+        }
+    }
+
+    /// Write the sections of a machine configuration, enable last
+    async fn apply_machine_sections(&mut self, machine: &MachineConfig) -> Result<()> {
+        self.write_stepper_params().await
+            .map_err(|e| e.in_section(ConfigSection::Stepper))?;
+        if let Some(limits) = machine.soft_limits {
+            self.apply_soft_limits(limits).await
+                .map_err(|e| e.in_section(ConfigSection::SoftLimits))?;
+        }
+        if let Some(brake) = machine.brake {
+            self.apply_brake_config(brake).await
+                .map_err(|e| e.in_section(ConfigSection::Brake))?;
+        }
+        if let Some(homing) = &machine.homing {
+            self.apply_homing_config(homing).await
+                .map_err(|e| e.in_section(ConfigSection::Homing))?;
+        }
+        for path in &machine.paths {
+            self.apply_path_config(path).await
+                .map_err(|e| e.in_section(ConfigSection::Path(path.path_id)))?;
+        }
+        self.apply_enable_method().await
+            .map_err(|e| e.in_section(ConfigSection::Enable))
+    }
+
+    /// Write the stepper parameters set up by `init`, without enabling the motor
+    async fn write_stepper_params(&mut self) -> Result<()> {
+        // Set pulse per revolution
+        self.write_init(registers::PULSE_PER_REV, self.config.pulse_per_rev).await?;
+
+        // Set motor direction
+        self.write_init(registers::MOTOR_DIRECTION, self.config.direction.to_bit()).await?;
+
+        // Set peak current
+        let peak_current = common::peak_current_raw(self.config.phase_current, self.config.peak_current_scale)?;
+        self.write_init(registers::PEAK_CURRENT, peak_current).await?;
+
+        // Set motor inductance
+        self.write_init(registers::MOTOR_INDUCTANCE, self.config.inductance.min(10000)).await
+    }
+
+    /// Enable the motor as the configured `enable_method` says
+    async fn apply_enable_method(&mut self) -> Result<()> {
+        match self.config.enable_method {
+            EnableMethod::None => Ok(()),
+            EnableMethod::ForcedEnable => self.forced_enable_by_software(true).await,
+            EnableMethod::Rs485ControlWord => self.enable_via_control_word(true).await,
+        }
+    }
+
+    /// Select this client's slave ID if auto reselect is enabled
+    fn reselect(&mut self) {
+        if self.auto_reselect {
+            self.ctx.set_slave(Slave::from(self.slave_id));
+        }
+    }
+
+    /// Report a transaction to the timer, if any
+    fn record(&self, op: TransactionOp, addr: u16, start: Instant) {
+        if let Some(timer) = &self.timer {
+            timer.record(op, addr, start.elapsed());
+        }
+    }
+
+    /// Write a single holding register
+    async fn write_register(&mut self, addr: u16, value: u16) -> Result<()> {
+        self.reselect();
+        let start = Instant::now();
+        let result = self.ctx.write_single_register(addr, value).await;
+        self.record(TransactionOp::Write, addr, start);
+        result
+            .map_err(|e| Em2rsError::from(e).at(addr))?
+            .map_err(|e| Em2rsError::from(e).at(addr))?;
+        if let Some(delay) = self.request_delay {
+            B::sleep(delay).await;
+        }
+        Ok(())
+    }
+
+    /// Write a register set up by `init`, verified if `with_verified_init` is on
+    async fn write_init(&mut self, addr: u16, value: u16) -> Result<()> {
+        if self.verify_init {
+            self.write_register_verified(addr, value).await
+        } else {
+            self.write_register(addr, value).await
+        }
+    }
+
+    /// Write a command register, then wait for the command settle delay
+    async fn write_command(&mut self, addr: u16, value: u16) -> Result<()> {
+        self.write_register(addr, value).await?;
+        if !self.command_settle.is_zero() {
+            B::sleep(self.command_settle).await;
+        }
+        Ok(())
+    }
+
+    /// Write multiple holding registers
+    async fn write_registers(&mut self, addr: u16, values: &[u16]) -> Result<()> {
+        self.reselect();
+        let start = Instant::now();
+        let result = self.ctx.write_multiple_registers(addr, values).await;
+        self.record(TransactionOp::Write, addr, start);
+        result
+            .map_err(|e| Em2rsError::from(e).at(addr))?
+            .map_err(|e| Em2rsError::from(e).at(addr))?;
+        if let Some(delay) = self.request_delay {
+            B::sleep(delay).await;
+        }
+        Ok(())
+    }
+
+    /// Read holding registers
+    ///
+    /// Fails with `Em2rsError::ShortResponse` if fewer registers than requested
+    /// are returned, so callers can index the result safely.
+    async fn read_registers(&mut self, addr: u16, count: u16) -> Result<Vec<u16>> {
+        self.reselect();
+        let start = Instant::now();
+        let result = self.ctx.read_holding_registers(addr, count).await;
+        self.record(TransactionOp::Read, addr, start);
+        let data = result
+            .map_err(|e| Em2rsError::from(e).at(addr))?
+            .map_err(|e| Em2rsError::from(e).at(addr))?;
+        if let Some(delay) = self.request_delay {
+            B::sleep(delay).await;
+        }
+        if data.len() < count as usize {
+            let err = Em2rsError::ShortResponse { expected: count as usize, got: data.len() };
+            return Err(err.at(addr));
+        }
+        Ok(data)
+    }
+
+    /// Send a control word command
+    async fn set_control_word(&mut self, command: ControlWord) -> Result<()> {
+        self.write_command(registers::CONTROL_WORD, command.into()).await
+    }
+
+    /// Poll the save status word until the save in progress finishes
+    async fn wait_for_save(&mut self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            match self.get_save_status().await? {
+                Some(SaveParameterStatus::SaveSuccessfully) => return Ok(()),
+                Some(SaveParameterStatus::FailedToSave) => {
+                    return Err(Em2rsError::OperationFailed("EEPROM save failed".into()));
+                }
+                None => {}
+            }
+            if start.elapsed() >= timeout {
+                return Err(Em2rsError::Timeout(timeout));
+            }
+            B::sleep(STATUS_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Send PR control command
+    async fn set_pr_control(&mut self, command: PrControlCommand) -> Result<()> {
+        self.write_command(registers::PR_CTRL, command.into()).await
+    }
+
+    /// Fail if a path would not move the motor when run
+    async fn check_path_runnable(&mut self, path_id: u8) -> Result<()> {
+        let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+        let ctrl = self.read_registers(base + registers::PATH_CTRL_OFFSET, 1).await?[0];
+        let velocity = self.read_registers(base + registers::PATH_VELOCITY_OFFSET, 1).await?[0];
+        match ctrl & 0x000F {
+            t if t == u16::from(PathMotionType::NoAction) => Err(Em2rsError::InvalidParameter(
+                format!("path {} is not configured (motion type NoAction)", path_id),
+            )),
+            t if t != u16::from(PathMotionType::Homing) && velocity == 0 => Err(
+                Em2rsError::InvalidParameter(format!("path {} has zero velocity", path_id)),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Poll the status until the homing just started completes
+    ///
+    /// Shared by `wait_for_homing_complete` and `home_with_progress`, see there
+    /// for when homing counts as complete or failed. `on_progress`, if any, is
+    /// given the position read after each status poll while homing runs.
+    pub(crate) async fn poll_homing<F: FnMut(i32)>(
+        &mut self,
+        poll_interval: Duration,
+        timeout: Duration,
+        mut on_progress: Option<F>,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let mut armed = false;
+        loop {
+            let status = self.get_motion_status().await?;
+            if status.is_fault() {
+                let alarm = self.get_current_alarm().await?;
+                return Err(Em2rsError::HomingFailed(format!("drive fault: {}", alarm)));
+            }
+            if !status.is_homing_complete() || status.is_running() {
+                armed = true;
+            } else if armed {
+                return Ok(());
+            }
+            if let Some(on_progress) = &mut on_progress {
+                on_progress(self.get_current_position().await?);
+            }
+            if start.elapsed() >= timeout {
+                return Err(Em2rsError::Timeout(timeout));
+            }
+            B::sleep(poll_interval).await;
+        }
+    }
+
+    /// Set a reduced phase current, returning the peak current register it replaced
+    pub(crate) async fn override_peak_current(&mut self, phase_current: f32) -> Result<u16> {
+        let original = self.read_registers(registers::PEAK_CURRENT, 1).await?[0];
+        self.set_peak_current(phase_current).await?;
+        Ok(original)
+    }
+
+    /// Write global control flags changed by `modify`, returning the value replaced
+    ///
+    /// Bits not covered by `GlobalControlFlags` are kept.
+    pub(crate) async fn override_global_control(
+        &mut self,
+        modify: impl FnOnce(&mut GlobalControlFlags),
+    ) -> Result<u16> {
+        let original = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+        let mut ctrl_flags = GlobalControlFlags::from(original);
+        modify(&mut ctrl_flags);
+        let modified = (original & !GlobalControlFlags::MASK) | u16::from(ctrl_flags);
+        self.write_register(registers::PR_GLOBAL_CTRL_FCT, modified).await?;
+        Ok(original)
+    }
+
+    /// Write back `original` after an overridden section that produced `result`
+    ///
+    /// If both `result` and the restore failed, `result` is returned and the
+    /// restore failure, of `what`, is logged.
+    pub(crate) async fn restore_register<T>(
+        &mut self,
+        addr: u16,
+        original: u16,
+        result: Result<T>,
+        what: &str,
+    ) -> Result<T> {
+        let restored = self.write_register(addr, original).await;
+        match (result, restored) {
+            (Ok(value), Ok(())) => Ok(value),
+            (Ok(_), Err(e)) => Err(e),
+            (Err(e), restored) => {
+                if let Err(restore_err) = restored {
+                    log::warn!("failed to restore {what}: {restore_err}");
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Define the client operations once for both clients
+///
+/// Each builder and method becomes a method of `ClientCore`, with its body,
+/// and a wrapper on `Em2rsClient`, with its docs, which forwards to it. The
+/// `Em2rsSyncClient` wrapper runs the same future with [`block_on`] and links
+/// to the async docs.
+macro_rules! client_api {
+    (
+        builders {
+            $(
+                $(#[$battr:meta])*
+                pub fn $builder:ident(mut $bself:ident, $barg:ident: $bty:ty) -> Self $bbody:block
+            )*
+        }
+
+        methods {
+            $(
+                $(#[$attr:meta])*
+                pub async fn $name:ident(&mut $self:ident $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty $body:block
+            )*
+        }
+    ) => {
+        impl<B: Backend> ClientCore<B> {
+            $(
+                fn $builder(mut $bself, $barg: $bty) -> Self $bbody
+            )*
+
+            $(
+                #[allow(clippy::too_many_arguments)]
+                pub(crate) async fn $name(&mut $self $(, $arg: $ty)*) -> $ret $body
+            )*
+        }
+
+        impl Em2rsClient {
+            $(
+                $(#[$battr])*
+                pub fn $builder(self, $barg: $bty) -> Self {
+                    Self { core: self.core.$builder($barg) }
+                }
+            )*
+
+            $(
+                $(#[$attr])*
+                pub async fn $name(&mut self $(, $arg: $ty)*) -> $ret {
+                    self.core.$name($($arg),*).await
+                }
+            )*
+        }
+
+        impl Em2rsSyncClient {
+            $(
+                $(#[$battr])*
+                pub fn $builder(self, $barg: $bty) -> Self {
+                    Self { core: self.core.$builder($barg) }
+                }
+            )*
+
+            $(
+                #[doc = concat!("Blocking counterpart of [`Em2rsClient::", stringify!($name), "`]")]
+                #[allow(clippy::too_many_arguments)]
+                pub fn $name(&mut self $(, $arg: $ty)*) -> $ret {
+                    block_on(self.core.$name($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+client_api! {
+    builders {
+        /// Enable strict mode
+        ///
+        /// In strict mode, suspicious configurations that would otherwise only log a
+        /// warning are rejected with an error.
+        pub fn with_strict_mode(mut self, strict: bool) -> Self {
+            self.strict = strict;
+            self
+        }
+
+        /// Select this client's slave ID before every transaction
+        ///
+        /// Guarantees each request targets this motor whatever used the context
+        /// last. Off by default; recommended when a context is handed between
+        /// clients for several motors on the same bus.
+        pub fn with_auto_reselect(mut self, auto_reselect: bool) -> Self {
+            self.auto_reselect = auto_reselect;
+            self
+        }
+
+        /// Read back every configuration register written by `init`
+        ///
+        /// `init` then fails with `Em2rsError::VerificationFailed` if a value did
+        /// not land, at the cost of one extra read per register. Off by default.
+        pub fn with_verified_init(mut self, verify: bool) -> Self {
+            self.verify_init = verify;
+            self
+        }
+
+        /// Use a non-default location for the path blocks
+        ///
+        /// Every path method addresses its registers through this layout. See
+        /// [`PathLayout`].
+        pub fn with_path_layout(mut self, layout: PathLayout) -> Self {
+            self.path_layout = layout;
+            self
+        }
+
+        /// Report the duration of every Modbus transaction to `timer`
+        pub fn with_transaction_timer(mut self, timer: Arc<dyn TransactionTimer>) -> Self {
+            self.timer = Some(timer);
+            self
+        }
+
+        /// Wait `delay` after every Modbus request
+        ///
+        /// For slow RS485 transceivers or long cable runs that need more than the
+        /// inter-frame delay. Replaces the delay the `modbus-delay` feature derives
+        /// from the baud rate, and applies with or without the feature;
+        /// `Duration::ZERO` disables it.
+        pub fn with_request_delay(mut self, delay: Duration) -> Self {
+            self.request_delay = Some(delay).filter(|d| !d.is_zero());
+            self
+        }
+
+        /// Wait after each control word and PR control command
+        ///
+        /// The drive may take a moment before its status reflects a command such
+        /// as `start_path` or `manual_zero`, so a status read right after it can
+        /// see the previous state. This delay is applied after those commands
+        /// only, on top of any inter-frame delay. None by default.
+        pub fn with_command_settle_delay(mut self, delay: Duration) -> Self {
+            self.command_settle = delay;
+            self
+        }
+    }
+
+    methods {
+        /// Change the drive's Modbus slave ID and follow it
+        ///
+        /// Writes `RS485_ID` and saves parameters to EEPROM while still addressing
+        /// the old ID, then switches this client, its configuration and the
+        /// context to `new_id` (1-247). The drive may keep answering on the old ID
+        /// until it is power cycled; until then, requests from this client go
+        /// unanswered.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.change_slave_id(7).await.unwrap();
+        /// assert_eq!(drive.get(registers::RS485_ID), Some(7));
+        /// assert_eq!(client.slave_id(), 7);
+        /// assert_eq!(client.config().slave_id, 7);
+        ///
+        /// // Later requests address the new ID
+        /// drive.answer_only([7]);
+        /// client.get_version().await.unwrap();
+        /// # });
+        /// ```
+        pub async fn change_slave_id(&mut self, new_id: u8) -> Result<()> {
+            common::check_slave_id(new_id)?;
+            self.ctx.set_slave(Slave::from(self.slave_id));
+            self.write_register(registers::RS485_ID, u16::from(new_id)).await?;
+            self.save_param_eeprom().await?;
+            self.slave_id = new_id;
+            self.config.slave_id = new_id;
+            self.ctx.set_slave(Slave::from(new_id));
+            Ok(())
+        }
+
+        /// Change the drive's RS485 baud rate
+        ///
+        /// Writes `RS485_BAUDRATE` and saves parameters to EEPROM. The drive keeps
+        /// its current rate until it is power cycled; reconnect at the new rate
+        /// afterwards, e.g. with `baud.bits_per_second()`.
+        pub async fn set_baudrate(&mut self, baud: Baudrate) -> Result<()> {
+            self.write_register(registers::RS485_BAUDRATE, baud.into()).await?;
+            self.save_param_eeprom().await
+        }
+
+        /// Initialize the stepper motor with configured parameters
+        pub async fn init(&mut self) -> Result<()> {
+            self.ctx.set_slave(Slave::from(self.slave_id));
+            self.write_stepper_params().await?;
+            self.apply_enable_method().await
+        }
+
+        /// Apply a whole-machine configuration
+        ///
+        /// Sections are applied in the order documented on [`MachineConfig`]. The
+        /// stepper configuration replaces the one given at construction once every
+        /// section is written; its slave ID must match this client's. On failure
+        /// the error is an `Em2rsError::Section` naming the section that could not
+        /// be applied, and the client keeps its previous configuration.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, EnableMethod};
+        /// use em2rs::{MachineConfig, PathConfig, SoftLimits, StepperConfig};
+        /// use tokio_modbus::ExceptionCode;
+        /// let stepper = StepperConfig::new(1, 20000).with_enable_method(EnableMethod::ForcedEnable);
+        /// let mut machine = MachineConfig::new(stepper);
+        /// machine.soft_limits = Some(SoftLimits { min: 0, max: 100_000 });
+        /// machine.paths.push(PathConfig::new(0).unwrap());
+        ///
+        /// // The motor is enabled once every other section is written
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.apply_machine_config(&machine).await.unwrap();
+        /// let writes = drive.writes();
+        /// assert_eq!(writes[0], (registers::PULSE_PER_REV, vec![20000]));
+        /// assert_eq!(writes.last(), Some(&(registers::FORCED_ENA, vec![1])));
+        /// assert_eq!(client.config().pulse_per_rev, 20000);
+        ///
+        /// // A failed section leaves the motor disabled and the configuration unchanged
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// drive.fail_at(registers::PATH0_BASE, ExceptionCode::IllegalDataAddress);
+        /// assert!(client.apply_machine_config(&machine).await.is_err());
+        /// assert!(drive.writes().iter().all(|(addr, _)| *addr != registers::FORCED_ENA));
+        /// assert_eq!(client.config().pulse_per_rev, 10000);
+        /// # });
+        /// ```
+        pub async fn apply_machine_config(&mut self, machine: &MachineConfig) -> Result<()> {
+            if machine.stepper.slave_id != self.slave_id {
+                return Err(Em2rsError::InvalidParameter(format!(
+                    "machine config is for slave {}, client is slave {}",
+                    machine.stepper.slave_id, self.slave_id
+                )));
+            }
+            self.ctx.set_slave(Slave::from(self.slave_id));
+
+            let previous = std::mem::replace(&mut self.config, machine.stepper.clone());
+            let result = self.apply_machine_sections(machine).await;
+            if result.is_err() {
+                self.config = previous;
+            }
+            result
+        }
+
+        /// Read holding registers not covered by a dedicated method
+        ///
+        /// Goes through the same slave selection, error context and inter-frame
+        /// delay as every other call. Between 1 and 125 registers can be read, the
+        /// Modbus limit for one request.
+        pub async fn read_holding(&mut self, addr: u16, count: u16) -> Result<Vec<u16>> {
+            common::check_read_count(count)?;
+            self.read_registers(addr, count).await
+        }
+
+        /// Write a single holding register not covered by a dedicated method
+        ///
+        /// Goes through the same slave selection, error context and inter-frame
+        /// delay as every other call. A Modbus exception from the drive is
+        /// returned with the register written, as for every write:
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// use tokio_modbus::ExceptionCode;
+        /// let drive = MemoryDrive::new();
+        /// drive.fail_at(registers::PEAK_CURRENT, ExceptionCode::IllegalDataValue);
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// let err = client.write_holding(registers::PEAK_CURRENT, 28).await.unwrap_err();
+        /// assert_eq!(err.register(), Some(registers::PEAK_CURRENT));
+        /// assert_eq!(err.to_string(), "Modbus exception: IllegalDataValue at register 0x0191");
+        /// # });
+        /// ```
+        pub async fn write_holding(&mut self, addr: u16, value: u16) -> Result<()> {
+            self.write_register(addr, value).await
+        }
+
+        /// Write a single holding register and read it back
+        ///
+        /// Fails with `Em2rsError::VerificationFailed` if the drive holds another
+        /// value afterwards. Not meant for command registers such as
+        /// `CONTROL_WORD`, which do not keep the value written.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, Em2rsError, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.write_register_verified(registers::MOTOR_INDUCTANCE, 1000).await.unwrap();
+        ///
+        /// // The drive keeps its previous value
+        /// drive.set(registers::PEAK_CURRENT, 20);
+        /// drive.ignore_writes(registers::PEAK_CURRENT);
+        /// let err = client.write_register_verified(registers::PEAK_CURRENT, 28).await.unwrap_err();
+        /// assert!(matches!(
+        ///     err,
+        ///     Em2rsError::VerificationFailed { addr: registers::PEAK_CURRENT, expected: 28, actual: 20 }
+        /// ));
+        /// assert_eq!(err.register(), Some(registers::PEAK_CURRENT));
+        /// # });
+        /// ```
+        pub async fn write_register_verified(&mut self, addr: u16, value: u16) -> Result<()> {
+            self.write_register(addr, value).await?;
+            let actual = self.read_registers(addr, 1).await?[0];
+            if actual != value {
+                return Err(Em2rsError::VerificationFailed { addr, expected: value, actual });
+            }
+            Ok(())
+        }
+
+        /// Write a block of contiguous holding registers in a single request
+        ///
+        /// For advanced use, e.g. writing a whole path block in one frame. Between
+        /// 1 and 123 values can be written, the Modbus limit for one request.
+        pub async fn write_holding_block(&mut self, addr: u16, values: &[u16]) -> Result<()> {
+            common::check_write_count(values.len())?;
+            self.write_registers(addr, values).await
+        }
+
+        /// Read raw register ranges for diagnostics
+        ///
+        /// Each range is read in requests of up to 125 registers, the Modbus limit.
+        /// When a request fails with a Modbus exception, its registers are read one
+        /// by one and those answering with an exception are left out of the map.
+        /// Other errors abort the dump.
+        pub async fn dump_registers(&mut self, ranges: &[RangeInclusive<u16>]) -> Result<BTreeMap<u16, u16>> {
+            let is_exception = |e: &Em2rsError| matches!(e.root_cause(), Em2rsError::ModbusException(_));
+            let mut dump = BTreeMap::new();
+            for range in ranges {
+                let end = u32::from(*range.end());
+                let mut start = u32::from(*range.start());
+                while start <= end {
+                    let addr = start as u16;
+                    let count = (end - start + 1).min(125) as u16;
+                    match self.read_registers(addr, count).await {
+                        Ok(data) => dump.extend(data.into_iter().enumerate().map(|(i, v)| (addr + i as u16, v))),
+                        Err(e) if is_exception(&e) => {
+                            for addr in addr..=addr + (count - 1) {
+                                match self.read_registers(addr, 1).await {
+                                    Ok(data) => {
+                                        dump.insert(addr, data[0]);
+                                    }
+                                    Err(e) if is_exception(&e) => {}
+                                    Err(e) => return Err(e),
+                                }
+                            }
+                        }
+                        Err(e) => return Err(e),
+                    }
+                    start += u32::from(count);
+                }
+            }
+            Ok(dump)
+        }
+
+        /// Set pulses per revolution (200-51200)
+        ///
+        /// The EM2RS has no separate electronic gear numerator/denominator
+        /// registers: pulse-input scaling is set entirely through this value. A gear
+        /// ratio `num/den` relative to a base resolution is expressed as
+        /// `base * den / num` pulses per revolution.
+        pub async fn set_pulse_per_rev(&mut self, pulse_per_rev: u16) -> Result<()> {
+            common::check_pulse_per_rev(pulse_per_rev)?;
+            self.write_register(registers::PULSE_PER_REV, pulse_per_rev).await?;
+            self.config.pulse_per_rev = pulse_per_rev;
+            Ok(())
+        }
+
+        /// Get pulses per revolution
+        ///
+        /// The EM2RS runs open loop without an encoder, so there is no separate
+        /// internal counter resolution: the position counter counts command pulses
+        /// and one revolution is exactly this many counts.
+        pub async fn get_pulse_per_rev(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::PULSE_PER_REV, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Get the motor model identified by the drive
+        pub async fn get_motor_model(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::MOTOR_MODEL, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Check the configured pulses per revolution against the motor model
+        ///
+        /// The drive does not report which resolutions suit a model, so
+        /// `supported_range` maps the model read from `MOTOR_MODEL` to the accepted
+        /// range, or `None` for models it doesn't know, which are accepted. An out
+        /// of range resolution logs a warning, or returns an error in strict mode.
+        pub async fn validate_against_motor(
+            &mut self,
+            supported_range: impl Fn(u16) -> Option<RangeInclusive<u16>>,
+        ) -> Result<()> {
+            let model = self.get_motor_model().await?;
+            let Some(range) = supported_range(model) else {
+                return Ok(());
+            };
+            if !range.contains(&self.config.pulse_per_rev) {
+                let msg = format!(
+                    "pulse per revolution {} outside {}-{} supported by motor model {:#06x}",
+                    self.config.pulse_per_rev, range.start(), range.end(), model
+                );
+                if self.strict {
+                    return Err(Em2rsError::InvalidParameter(msg));
+                }
+                log::warn!("{msg}");
+            }
+            Ok(())
+        }
+
+        /// Get the motor direction setting
+        pub async fn get_motor_direction(&mut self) -> Result<Direction> {
+            let data = self.read_registers(registers::MOTOR_DIRECTION, 1).await?;
+            Ok(Direction::from_bit(data[0]))
+        }
+
+        /// Check the drive's direction settings against the intended ones
+        ///
+        /// `MOTOR_DIRECTION` sets which way the motor turns for every command:
+        /// the homing direction (bit 0 of `HOME_MODE`), jog commands and path
+        /// moves are all relative to it, so reversing it reverses them together.
+        /// A mismatch between them therefore comes from the homing direction or
+        /// the motor direction alone, which are checked here against `homing` and
+        /// `StepperConfig::direction`. Jog directions are given per command and
+        /// not stored by the drive. A mismatch logs a warning, or returns an error
+        /// in strict mode.
+        pub async fn validate_directions(&mut self, homing: Direction) -> Result<()> {
+            let motor = self.get_motor_direction().await?;
+            let home_mode = self.read_registers(registers::HOME_MODE, 1).await?[0];
+            let checks = [
+                ("motor", motor, self.config.direction),
+                ("homing", Direction::from_bit(home_mode), homing),
+            ];
+            for (name, actual, expected) in checks {
+                if actual != expected {
+                    let msg = format!("{} direction is {:?} on the drive, expected {:?}", name, actual, expected);
+                    if self.strict {
+                        return Err(Em2rsError::InvalidParameter(msg));
+                    }
+                    log::warn!("{msg}");
+                }
+            }
+            Ok(())
+        }
+
+        /// Set peak current based on phase current
+        ///
+        /// `PEAK_CURRENT` is in 0.1 A and receives
+        /// `phase_current * peak_current_scale`, truncated. The default scale of
+        /// 14 is the peak to RMS ratio of 1.4 times 10 units per ampere, so 2.0 A
+        /// is written as 28. Fails with `Em2rsError::InvalidParameter` if the
+        /// result is negative, not a number or above 65535. The highest current
+        /// the drive accepts depends on the model and is not checked here.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        ///
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.set_peak_current(1.05).await.unwrap(); // 14.7, truncated
+        /// assert_eq!(drive.get(registers::PEAK_CURRENT), Some(14));
+        /// assert!(client.set_peak_current(5000.0).await.is_err());
+        /// # });
+        /// ```
+        pub async fn set_peak_current(&mut self, phase_current: f32) -> Result<()> {
+            let peak_current = common::peak_current_raw(phase_current, self.config.peak_current_scale)?;
+            self.write_register(registers::PEAK_CURRENT, peak_current).await
+        }
+
+        /// Get the phase current back from the peak current register
+        ///
+        /// The register is converted with the scale configured now: if the scale
+        /// changed since the value was written, the result reflects the new scale.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        ///
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.set_peak_current(2.0).await.unwrap(); // written as 28 with scale 14
+        /// client.set_peak_current_scale(10.0);
+        /// assert_eq!(client.get_peak_current().await.unwrap(), 2.8); // read as 28 / 10
+        /// # });
+        /// ```
+        pub async fn get_peak_current(&mut self) -> Result<f32> {
+            let data = self.read_registers(registers::PEAK_CURRENT, 1).await?;
+            Ok(common::phase_current_from_raw(data[0], self.config.peak_current_scale))
+        }
+
+        /// Set the current held at standstill, as a percentage of peak current
+        ///
+        /// This is also the holding torque: the EM2RS has a single standstill
+        /// setting, `STANDBY_CURRENT_PERCENT`, applied once the motor has been
+        /// stopped for the standby switching time. There is no separate holding
+        /// torque register; keep this high on axes that must hold a load.
+        pub async fn set_standby_current_percent(&mut self, percent: u16) -> Result<()> {
+            common::check_standby_percent(percent)?;
+            self.write_register(registers::STANDBY_CURRENT_PERCENT, percent).await
+        }
+
+        /// Get the standstill current percentage
+        pub async fn get_standby_current_percent(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::STANDBY_CURRENT_PERCENT, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Set the time at standstill before switching to standby current (ms)
+        pub async fn set_standby_switching_time(&mut self, ms: u16) -> Result<()> {
+            self.write_register(registers::SWITCHING_TIME_STANDBY, ms).await
+        }
+
+        /// Get the time at standstill before switching to standby current (ms)
+        pub async fn get_standby_switching_time(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::SWITCHING_TIME_STANDBY, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Set motor inductance (max 10000)
+        pub async fn set_motor_inductance(&mut self, inductance: u16) -> Result<()> {
+            let ind = inductance.min(10000);
+            self.write_register(registers::MOTOR_INDUCTANCE, ind).await
+        }
+
+        /// Get the motor inductance currently held by the drive
+        ///
+        /// The EM2RS register map documents no separate register for the
+        /// inductance identified by auto-tuning, so the value in use is read back
+        /// from `MOTOR_INDUCTANCE`, the register written by
+        /// `set_motor_inductance`. Read it after a power-on
+        /// tuning (see `AUTO_TUNING_POWER_ON`) and compare it with
+        /// `StepperConfig::inductance` to check what the drive settled on, e.g.
+        /// before hard-coding it and disabling power-on tuning.
+        pub async fn get_identified_inductance(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::MOTOR_INDUCTANCE, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Read the motor electrical parameters
+        ///
+        /// `MOTOR_MODEL` to `CURRENT_LOOP_KC` are read in one request, the peak
+        /// current and inductance separately. See [`MotorParams`].
+        pub async fn read_motor_params(&mut self) -> Result<MotorParams> {
+            let peak_current = self.read_registers(registers::PEAK_CURRENT, 1).await?[0];
+            let inductance = self.read_registers(registers::MOTOR_INDUCTANCE, 1).await?[0];
+            let count = registers::CURRENT_LOOP_KC - registers::MOTOR_MODEL + 1;
+            let data = self.read_registers(registers::MOTOR_MODEL, count).await?;
+            let at = |addr: u16| data[(addr - registers::MOTOR_MODEL) as usize];
+            Ok(MotorParams {
+                peak_current,
+                inductance,
+                motor_model: at(registers::MOTOR_MODEL),
+                back_emf_coef: at(registers::BACK_EMF_COEF),
+                current_loop_proportional_kp: at(registers::CURRENT_LOOP_PROPORTIONAL_KP),
+                current_loop_ki: at(registers::CURRENT_LOOP_KI),
+                current_loop_kp: at(registers::CURRENT_LOOP_KP),
+                current_loop_kc: at(registers::CURRENT_LOOP_KC),
+            })
+        }
+
+        /// Write motor electrical parameters, e.g. read from another drive
+        ///
+        /// Each register is written on its own so the undocumented registers
+        /// between them are left untouched. The peak current is written last.
+        /// Values are only held in RAM until `save_param_eeprom` is called.
+        pub async fn write_motor_params(&mut self, params: &MotorParams) -> Result<()> {
+            self.write_register(registers::MOTOR_MODEL, params.motor_model).await?;
+            self.write_register(registers::BACK_EMF_COEF, params.back_emf_coef).await?;
+            self.write_register(registers::CURRENT_LOOP_PROPORTIONAL_KP, params.current_loop_proportional_kp).await?;
+            self.write_register(registers::CURRENT_LOOP_KI, params.current_loop_ki).await?;
+            self.write_register(registers::CURRENT_LOOP_KP, params.current_loop_kp).await?;
+            self.write_register(registers::CURRENT_LOOP_KC, params.current_loop_kc).await?;
+            self.write_register(registers::MOTOR_INDUCTANCE, params.inductance).await?;
+            self.write_register(registers::PEAK_CURRENT, params.peak_current).await
+        }
+
+        /// Apply motion smoothing parameters
+        pub async fn apply_motion_smoothing(&mut self, smoothing: MotionSmoothing) -> Result<()> {
+            self.write_register(registers::CMD_FILTER_TIME, smoothing.cmd_filter_time).await
+        }
+
+        /// Read motion smoothing parameters back
+        pub async fn get_motion_smoothing(&mut self) -> Result<MotionSmoothing> {
+            let data = self.read_registers(registers::CMD_FILTER_TIME, 1).await?;
+            Ok(MotionSmoothing { cmd_filter_time: data[0] })
+        }
+
+        /// Enable or disable forced software enable
+        pub async fn forced_enable_by_software(&mut self, enable: bool) -> Result<()> {
+            let value = if enable { 0x0001 } else { 0x0000 };
+            self.write_register(registers::FORCED_ENA, value).await
+        }
+
+        /// Enable or disable the motor through the RS485 control word
+        ///
+        /// When the drive is controlled over RS485, `FORCED_ENA` may have no effect
+        /// and the enable bit of `RS485_CONTROL_WORD` must be used instead.
+        pub async fn enable_via_control_word(&mut self, enable: bool) -> Result<()> {
+            let mut reg = self.read_registers(registers::RS485_CONTROL_WORD, 1).await?[0];
+            if enable {
+                reg |= flags::RS485_CW_ENABLE;
+            } else {
+                reg &= !flags::RS485_CW_ENABLE;
+            }
+            self.write_register(registers::RS485_CONTROL_WORD, reg).await
+        }
+
+        /// Save parameters to EEPROM
+        ///
+        /// Registers only expose the values active in RAM: the drive offers no
+        /// way to read what is stored in EEPROM, so unsaved changes cannot be
+        /// detected from the drive. Track them on the host, e.g. by comparing
+        /// `config_fingerprint` with the one taken at the last save.
+        pub async fn save_param_eeprom(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::SaveParamEeprom).await
+        }
+
+        /// Get the outcome of the last EEPROM save, `None` while still saving
+        pub async fn get_save_status(&mut self) -> Result<Option<SaveParameterStatus>> {
+            let data = self.read_registers(registers::SAVE_PARAMETER_STATUS_WORD, 1).await?;
+            Ok(SaveParameterStatus::from_word(data[0]))
+        }
+
+        /// Check if an EEPROM save is still in progress
+        ///
+        /// The drive rejects other commands while saving. Poll this after
+        /// `save_param_eeprom` instead of sleeping a fixed time, then check
+        /// `get_save_status` for the outcome.
+        pub async fn is_save_in_progress(&mut self) -> Result<bool> {
+            Ok(self.get_save_status().await?.is_none())
+        }
+
+        /// Reset parameters (excluding motor parameters)
+        pub async fn param_reset(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::ParamReset).await
+        }
+
+        /// Factory reset
+        pub async fn factory_reset(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::FactoryReset).await
+        }
+
+        /// Save I/O mapping to EEPROM
+        pub async fn save_mapping_eeprom(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::SaveMappingEeprom).await
+        }
+
+        /// Save I/O mapping to EEPROM and wait for the drive to confirm it
+        ///
+        /// Mapping saves report through the same status word as parameter saves,
+        /// polled here as with `is_save_in_progress`. Fails with
+        /// `Em2rsError::OperationFailed` if the drive reports the save failed, or
+        /// `Em2rsError::Timeout` if it is still saving after `timeout`.
+        pub async fn save_mapping_eeprom_verified(&mut self, timeout: Duration) -> Result<()> {
+            self.save_mapping_eeprom().await?;
+            self.wait_for_save(timeout).await
+        }
+
+        /// Reset the current alarm
+        pub async fn reset_current_alarm(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::ResetCurrentAlarm).await
+        }
+
+        /// Reset the alarm history
+        pub async fn reset_history_alarm(&mut self) -> Result<()> {
+            self.set_control_word(ControlWord::ResetHistoryAlarm).await
+        }
+
+        /// Jog the motor in specified direction
+        pub async fn jog_motor(&mut self, direction: Direction) -> Result<()> {
+            self.set_control_word(ControlWord::jog(direction)).await
+        }
+
+        /// Configure the jog registers and start a repetitive jog cycle
+        ///
+        /// The motor runs for the running time, pauses for the interval and
+        /// repeats, e.g. for burn-in testing, until stopped with
+        /// `stop_repetitive_jog`.
+        pub async fn start_repetitive_jog(&mut self, config: &RepetitiveJogConfig) -> Result<()> {
+            common::check_jog_velocity(config.velocity)?;
+            self.write_register(registers::JOG_VELOCITY, config.velocity).await?;
+            self.write_register(registers::INTERVAL, config.interval).await?;
+            self.write_register(registers::RUNNING_TIME, config.running_time).await?;
+            self.write_register(registers::ACC_DEC_TIME, config.acc_dec_time).await?;
+            self.jog_motor(config.direction).await
+        }
+
+        /// Stop a repetitive jog cycle
+        ///
+        /// Issues a quick stop, ending the run in progress or the pause. The jog
+        /// registers keep their values, so the next jog command or jog input runs
+        /// the same cycle; use `stop_and_wait` instead to also wait for
+        /// standstill before the next command.
+        pub async fn stop_repetitive_jog(&mut self) -> Result<()> {
+            self.stop_motor().await
+        }
+
+        /// Configure a digital input
+        ///
+        /// The EM2RS register map has no digital input filter or debounce time:
+        /// `CMD_FILTER_TIME` smooths the motion command, not the inputs. Chattering
+        /// switches must be debounced in hardware or on the host.
+        ///
+        /// The register holds the function code, plus `flags::SI_NC_INCR` for a
+        /// normally closed input:
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, DigitalInputFunction, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.configure_input(1, DigitalInputFunction::Org, false).await.unwrap();
+        /// client.configure_input(2, DigitalInputFunction::Org, true).await.unwrap();
+        /// assert_eq!(drive.get(registers::SI1), Some(0x0027));
+        /// assert_eq!(drive.get(registers::SI2), Some(0x00A7));
+        /// # });
+        /// ```
+        pub async fn configure_input(
+            &mut self,
+            input_no: u8,
+            function: DigitalInputFunction,
+            normally_closed: bool,
+        ) -> Result<()> {
+            let register = common::input_register(input_no)?;
+            let config = u16::from(function) + if normally_closed { flags::SI_NC_INCR } else { 0 };
+            self.write_register(register, config).await
+        }
+
+        /// Configure a digital output
+        ///
+        /// The register holds the function code, plus `flags::SO_NC_INCR` for a
+        /// normally closed output:
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, DigitalOutputFunction, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.configure_output(1, DigitalOutputFunction::AlarmOutput, false).await.unwrap();
+        /// client.configure_output(2, DigitalOutputFunction::AlarmOutput, true).await.unwrap();
+        /// assert_eq!(drive.get(registers::SO1), Some(0x0025));
+        /// assert_eq!(drive.get(registers::SO2), Some(0x00A5));
+        /// # });
+        /// ```
+        pub async fn configure_output(
+            &mut self,
+            output_no: u8,
+            function: DigitalOutputFunction,
+            normally_closed: bool,
+        ) -> Result<()> {
+            let register = common::output_register(output_no)?;
+            let config = u16::from(function) + if normally_closed { flags::SO_NC_INCR } else { 0 };
+            self.write_register(register, config).await
+        }
+
+        /// Map a digital input to jogging at a given velocity
+        ///
+        /// Configures the input (normally open) with
+        /// `DigitalInputFunction::JogVelocity` and sets `JOG_VELOCITY`, shared by
+        /// every jog input and `jog_motor`. The velocity must be 1-3000 RPM.
+        pub async fn configure_jog_input(&mut self, input_no: u8, velocity_rpm: u16) -> Result<()> {
+            common::check_jog_velocity(velocity_rpm)?;
+            self.configure_input(input_no, DigitalInputFunction::JogVelocity, false).await?;
+            self.write_register(registers::JOG_VELOCITY, velocity_rpm).await
+        }
+
+        /// Get digital input status
+        ///
+        /// The drive does not report which source is currently controlling it.
+        /// Inputs configured as `JogPositive` or `JogNegative` take priority over
+        /// RS485 commands while active: check their bits here to detect a
+        /// hardware input overriding the bus.
+        pub async fn get_input_status(&mut self) -> Result<DigitalInputStatus> {
+            let data = self.read_registers(registers::DIGITAL_INPUT_STATUS, 1).await?;
+            Ok(DigitalInputStatus(data[0]))
+        }
+
+        /// Get digital output status
+        pub async fn get_output_status(&mut self) -> Result<DigitalOutputStatus> {
+            let data = self.read_registers(registers::DIGITAL_OUTPUT_STATUS, 1).await?;
+            Ok(DigitalOutputStatus(data[0]))
+        }
+
+        /// Check if the holding brake is engaged
+        ///
+        /// Finds the digital output mapped to `DigitalOutputFunction::BrakeOutput`
+        /// and reads its state from `DIGITAL_OUTPUT_STATUS`. The brake output is
+        /// active while the brake is released, so the brake is engaged while it is
+        /// inactive. Fails if no output is mapped to the brake: the drive exposes no
+        /// actual velocity to compare against `THRESHOLD_BRAKE` instead.
+        pub async fn is_brake_engaged(&mut self) -> Result<bool> {
+            let outputs = self.read_registers(registers::SO1, registers::SO3 - registers::SO1 + 1).await?;
+            let brake = u16::from(DigitalOutputFunction::BrakeOutput);
+            let output_no = outputs
+                .iter()
+                .step_by(2)
+                .position(|&config| config & !flags::SO_NC_INCR == brake)
+                .ok_or_else(|| Em2rsError::OperationFailed("no digital output mapped to brake".into()))?;
+            let status = self.get_output_status().await?;
+            Ok(!status.is_output_active(output_no as u8 + 1))
+        }
+
+        /// Read bus voltage, input and output status in a single transaction
+        ///
+        /// `BUS_VOLTAGE` to `DIGITAL_OUTPUT_STATUS` are contiguous; motion status
+        /// and alarm registers live elsewhere and need their own reads. The bus
+        /// voltage is converted to volts as by `get_bus_voltage`.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// drive.set(registers::BUS_VOLTAGE, 481);
+        /// drive.set(registers::DIGITAL_INPUT_STATUS, 0b000_0101);
+        /// drive.set(registers::DIGITAL_OUTPUT_STATUS, 0b010);
+        /// let status = client.read_status_block().await.unwrap();
+        /// assert_eq!(status.bus_voltage, 48.1);
+        /// assert!(status.inputs.is_input_active(3));
+        /// assert!(!status.inputs.is_input_active(2));
+        /// assert!(status.outputs.is_output_active(2));
+        /// # });
+        /// ```
+        pub async fn read_status_block(&mut self) -> Result<StatusBlock> {
+            let count = registers::DIGITAL_OUTPUT_STATUS - registers::BUS_VOLTAGE + 1;
+            let data = self.read_registers(registers::BUS_VOLTAGE, count).await?;
+            let inputs = data[(registers::DIGITAL_INPUT_STATUS - registers::BUS_VOLTAGE) as usize];
+            let outputs = data[(registers::DIGITAL_OUTPUT_STATUS - registers::BUS_VOLTAGE) as usize];
+            Ok(StatusBlock {
+                bus_voltage: bus_voltage_from_raw(data[0]),
+                inputs: DigitalInputStatus(inputs),
+                outputs: DigitalOutputStatus(outputs),
+            })
+        }
+
+        /// Get the DC bus voltage in volts
+        ///
+        /// `BUS_VOLTAGE` counts in 0.1 V. Readings far outside any supply range
+        /// are converted as is rather than clamped, so a garbled value stays
+        /// visible; a failed read is an error, never a voltage.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// use tokio_modbus::ExceptionCode;
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// for (raw, volts) in [(0, 0.0), (1, 0.1), (481, 48.1), (65535, 6553.5)] {
+        ///     drive.set(registers::BUS_VOLTAGE, raw);
+        ///     assert_eq!(client.get_bus_voltage().await.unwrap(), volts);
+        /// }
+        ///
+        /// drive.fail_at(registers::BUS_VOLTAGE, ExceptionCode::IllegalDataAddress);
+        /// assert!(client.get_bus_voltage().await.is_err());
+        /// # });
+        /// ```
+        pub async fn get_bus_voltage(&mut self) -> Result<f32> {
+            let data = self.read_registers(registers::BUS_VOLTAGE, 1).await?;
+            Ok(bus_voltage_from_raw(data[0]))
+        }
+
+        /// Get motion status
+        pub async fn get_motion_status(&mut self) -> Result<MotionStatus> {
+            let data = self.read_registers(registers::MOTION_STATUS, 1).await?;
+            Ok(MotionStatus(data[0]))
+        }
+
+        /// Get the current motor position in pulses
+        ///
+        /// Reads the signed 32-bit position counter, high word first. The EM2RS
+        /// runs open loop without an encoder, so this is the commanded position:
+        /// lost steps are not detected. The drive has no separate feedback
+        /// position register.
+        ///
+        /// The counter has no multi-turn extension and wraps at the `i32`
+        /// bounds, so positions beyond that range must be tracked on the host.
+        /// The drive keeps no total travel counter either: for distance-based
+        /// maintenance, sum the deltas between successive reads on the host.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{position_words, registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// for position in [0, 5000, -5000, -1, i32::MIN, i32::MAX] {
+        ///     let [high, low] = position_words(position);
+        ///     drive.set(registers::CMD_POSITION_H, high);
+        ///     drive.set(registers::CMD_POSITION_L, low);
+        ///     assert_eq!(client.get_current_position().await.unwrap(), position);
+        /// }
+        /// # });
+        /// ```
+        pub async fn get_current_position(&mut self) -> Result<i32> {
+            let data = self.read_registers(registers::CMD_POSITION_H, 2).await?;
+            Ok(common::join_words(data[0], data[1]) as i32)
+        }
+
+        /// Check if path is completed
+        pub async fn is_path_completed(&mut self) -> Result<bool> {
+            let status = self.get_motion_status().await?;
+            Ok(status.is_path_complete())
+        }
+
+        /// Wait until the current path completes, polling every `poll_interval`
+        ///
+        /// Fails with `Em2rsError::OperationFailed` if the drive reports a fault,
+        /// or `Em2rsError::Timeout` if the path is still running after `timeout`.
+        pub async fn wait_for_path_complete(&mut self, poll_interval: Duration, timeout: Duration) -> Result<()> {
+            self.wait_until(&[StopCondition::PathComplete], poll_interval, timeout).await?;
+            Ok(())
+        }
+
+        /// Wait until any of `conditions` is met and return the first that is
+        ///
+        /// Conditions are checked in order every `poll_interval`. A drive fault
+        /// ends the wait: it is returned as `StopCondition::Fault` if listed, and
+        /// fails with `Em2rsError::OperationFailed` otherwise. Fails with
+        /// `Em2rsError::Timeout` if nothing fired within `timeout`.
+        pub async fn wait_until(
+            &mut self,
+            conditions: &[StopCondition],
+            poll_interval: Duration,
+            timeout: Duration,
+        ) -> Result<StopCondition> {
+            for condition in conditions {
+                if let StopCondition::InputActive(input_no) = *condition {
+                    common::check_input_no(input_no)?;
+                }
+            }
+            let needs_inputs = conditions.iter().any(|c| matches!(c, StopCondition::InputActive(_)));
+            let start = Instant::now();
+            loop {
+                let status = self.get_motion_status().await?;
+                let inputs = if needs_inputs { self.get_input_status().await? } else { DigitalInputStatus(0) };
+                let fired = conditions.iter().copied().find(|condition| match *condition {
+                    StopCondition::PathComplete => status.is_path_complete(),
+                    StopCondition::HomingComplete => status.is_homing_complete(),
+                    StopCondition::InputActive(input_no) => inputs.is_input_active(input_no),
+                    StopCondition::Fault => status.is_fault(),
+                });
+                if let Some(condition) = fired {
+                    return Ok(condition);
+                }
+                if status.is_fault() {
+                    return Err(Em2rsError::OperationFailed("fault while waiting".into()));
+                }
+                if start.elapsed() >= timeout {
+                    return Err(Em2rsError::Timeout(timeout));
+                }
+                B::sleep(poll_interval).await;
+            }
+        }
+
+        /// Check if homing is completed
+        pub async fn is_homing_completed(&mut self) -> Result<bool> {
+            let status = self.get_motion_status().await?;
+            Ok(status.is_homing_complete())
+        }
+
+        /// Wait for a homing sequence started with `start_homing` to complete
+        ///
+        /// The status is read every `poll_interval`. Fails with
+        /// `Em2rsError::HomingFailed` carrying the current alarm if the drive
+        /// reports a fault, and with `Em2rsError::Timeout` if homing has not
+        /// completed after `timeout`, e.g. when the home switch is never found or
+        /// a miswired switch leaves the motor pushing against a hard stop. A
+        /// standstill alone is not a failure: the motor pauses between the fast
+        /// and slow phases of a homing sequence.
+        ///
+        /// The homing complete flag stays set after an earlier homing, so it only
+        /// counts once it was seen clear or the motor was seen running since the
+        /// call, and with the motor at standstill.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use std::time::Duration;
+        /// use em2rs::registers::{self, flags};
+        /// use em2rs::{test_util::MemoryDrive, CurrentAlarm, Em2rsClient, Em2rsError, StepperConfig};
+        /// let poll = Duration::from_millis(1);
+        /// let timeout = Duration::from_millis(50);
+        ///
+        /// // Completes: the flag left by an earlier homing is not taken for this one
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// drive.script(registers::MOTION_STATUS, [
+        ///     flags::MS_HOMING_COMPLETE,
+        ///     flags::MS_HOMING_COMPLETE | flags::MS_RUNNING,
+        ///     flags::MS_RUNNING,
+        ///     0,
+        ///     flags::MS_RUNNING,
+        ///     flags::MS_HOMING_COMPLETE,
+        /// ]);
+        /// client.wait_for_homing_complete(poll, timeout).await.unwrap();
+        ///
+        /// // Times out: the stale flag never clears and the motor never moves
+        /// drive.set(registers::MOTION_STATUS, flags::MS_HOMING_COMPLETE);
+        /// let err = client.wait_for_homing_complete(poll, timeout).await.unwrap_err();
+        /// assert!(matches!(err, Em2rsError::Timeout(_)));
+        ///
+        /// // Fails on a drive fault
+        /// drive.set(registers::MOTION_STATUS, flags::MS_FAULT);
+        /// drive.set(registers::CURRENT_ALARM, CurrentAlarm::OVER_CURRENT);
+        /// let err = client.wait_for_homing_complete(poll, timeout).await.unwrap_err();
+        /// assert!(matches!(err, Em2rsError::HomingFailed(_)));
+        /// # });
+        /// ```
+        pub async fn wait_for_homing_complete(
+            &mut self,
+            poll_interval: Duration,
+            timeout: Duration,
+        ) -> Result<()> {
+            self.poll_homing(poll_interval, timeout, None::<fn(i32)>).await
+        }
+
+        /// Set CTRG effective edge (double edge or single)
+        pub async fn set_ctrg_effective_edge(&mut self, double_edge: bool) -> Result<()> {
+            let mut reg = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+            if double_edge {
+                reg |= 1 << 0;
+            } else {
+                reg &= !(1 << 0);
+            }
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, reg).await
+        }
+
+        /// Get the CTRG trigger configuration
+        pub async fn get_ctrg_config(&mut self) -> Result<CtrgConfig> {
+            Ok(self.get_global_control_flags().await?.into())
+        }
+
+        /// Set the CTRG edge and trigger type bits together
+        ///
+        /// Other bits of `PR_GLOBAL_CTRL_FCT` are preserved.
+        pub async fn set_ctrg_config(&mut self, ctrg: CtrgConfig) -> Result<()> {
+            let mut reg = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+            reg &= !(flags::PR_CTRG_DOUBLE_EDGE | flags::PR_CTRG_LEVEL_TRIGGER);
+            reg |= match ctrg {
+                CtrgConfig::SingleEdge => 0,
+                CtrgConfig::DoubleEdge => flags::PR_CTRG_DOUBLE_EDGE,
+                CtrgConfig::Level => flags::PR_CTRG_LEVEL_TRIGGER,
+            };
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, reg).await
+        }
+
+        /// Enable or disable soft limit control
+        ///
+        /// The register map has no setting for the action taken on a soft limit
+        /// violation (stop vs fault). To latch violations, watch the motion status
+        /// on the host and treat an unexpected stop at a limit as a fault.
+        pub async fn soft_limit_control(&mut self, enable: bool) -> Result<()> {
+            let mut reg = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+            if enable {
+                reg |= 1 << 1;
+            } else {
+                reg &= !(1 << 1);
+            }
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, reg).await
+        }
+
+        /// Set soft limit maximum position
+        pub async fn set_soft_limit_max(&mut self, max: u32) -> Result<()> {
+            let [msb, lsb] = common::split_words(max);
+            self.write_registers(registers::SOFT_LIMIT_P_H, &[msb, lsb]).await
+        }
+
+        /// Set soft limit maximum position as a signed value
+        pub async fn set_soft_limit_max_signed(&mut self, max: i32) -> Result<()> {
+            let [msb, lsb] = position_words(max);
+            self.write_registers(registers::SOFT_LIMIT_P_H, &[msb, lsb]).await
+        }
+
+        /// Set soft limit minimum position
+        pub async fn set_soft_limit_min(&mut self, min: u32) -> Result<()> {
+            let [msb, lsb] = common::split_words(min);
+            self.write_registers(registers::SOFT_LIMIT_N_H, &[msb, lsb]).await
+        }
+
+        /// Set soft limit minimum position as a signed value, e.g. below zero
+        pub async fn set_soft_limit_min_signed(&mut self, min: i32) -> Result<()> {
+            let [msb, lsb] = position_words(min);
+            self.write_registers(registers::SOFT_LIMIT_N_H, &[msb, lsb]).await
+        }
+
+        /// Set both soft limits and enable soft limit control
+        pub async fn apply_soft_limits(&mut self, limits: SoftLimits) -> Result<()> {
+            self.set_soft_limit_min(limits.min).await?;
+            self.set_soft_limit_max(limits.max).await?;
+            self.soft_limit_control(true).await
+        }
+
+        /// Read both soft limits back
+        pub async fn get_soft_limits(&mut self) -> Result<SoftLimits> {
+            let count = registers::SOFT_LIMIT_N_L - registers::SOFT_LIMIT_P_H + 1;
+            let data = self.read_registers(registers::SOFT_LIMIT_P_H, count).await?;
+            let at = |addr: u16| data[(addr - registers::SOFT_LIMIT_P_H) as usize];
+            let word = |h: u16, l: u16| common::join_words(at(h), at(l));
+            Ok(SoftLimits {
+                min: word(registers::SOFT_LIMIT_N_H, registers::SOFT_LIMIT_N_L),
+                max: word(registers::SOFT_LIMIT_P_H, registers::SOFT_LIMIT_P_L),
+            })
+        }
+
+        /// Start an absolute move on a path, checked against the soft limits
+        ///
+        /// When soft limits are enabled on the drive, a `target` outside them is
+        /// clamped to the nearest limit if `clamp` is set, or rejected with
+        /// `Em2rsError::InvalidParameter` otherwise. Limits and target are signed,
+        /// like the position counter. Returns whether the target was clamped. The
+        /// move is started but not awaited.
+        pub async fn move_to_clamped(&mut self, path_id: u8, target: i32, velocity: u16, clamp: bool) -> Result<bool> {
+            let path = PathConfig::new(path_id)?;
+            let mut position = target;
+            if self.get_global_control_flags().await?.soft_limit_enabled {
+                let limits = self.get_soft_limits().await?;
+                let (min, max) = (limits.min as i32, limits.max as i32);
+                if min > max {
+                    return Err(Em2rsError::InvalidParameter(format!(
+                        "soft limit min {} is above max {}",
+                        min, max
+                    )));
+                }
+                if !(min..=max).contains(&target) {
+                    if !clamp {
+                        return Err(Em2rsError::InvalidParameter(format!(
+                            "target {} is outside soft limits {}..={}",
+                            target, min, max
+                        )));
+                    }
+                    position = target.clamp(min, max);
+                }
+            }
+            let path = path.with_position(position as u32).with_velocity(velocity);
+            self.apply_path_config(&path).await?;
+            self.start_path(path_id).await?;
+            Ok(position != target)
+        }
+
+        /// Start a move of `delta` pulses from the current position
+        ///
+        /// Configures `path_id` (0-8) as a relative positioning move at `velocity`
+        /// RPM with the default ramps, then starts it. The drive adds `delta` to
+        /// wherever the motor is, so the position is not read first. The move is
+        /// started but not awaited.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        ///
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.move_relative(0, -1000, 300).await.unwrap();
+        /// let ctrl = drive.get(registers::PATH0_BASE + registers::PATH_CTRL_OFFSET).unwrap();
+        /// assert_ne!(ctrl & 0x0040, 0); // relative positioning
+        /// # });
+        /// ```
+        pub async fn move_relative(&mut self, path_id: u8, delta: i32, velocity: u16) -> Result<()> {
+            let path = PathConfig::new(path_id)?
+                .relative()
+                .with_position(delta as u32)
+                .with_velocity(velocity);
+            self.apply_path_config(&path).await?;
+            self.start_path(path_id).await
+        }
+
+        /// Apply holding brake timing and threshold
+        pub async fn apply_brake_config(&mut self, brake: BrakeConfig) -> Result<()> {
+            self.write_register(registers::DELAY_BRAKE_RELEASED, brake.release_delay).await?;
+            self.write_register(registers::DELAY_BRAKE_LOCKED, brake.lock_delay).await?;
+            self.set_brake_threshold_rpm(brake.threshold).await
+        }
+
+        /// Set the velocity below which the brake engages (RPM, 0-3000)
+        ///
+        /// `THRESHOLD_BRAKE` holds the velocity directly in RPM. When the motor
+        /// is disabled while turning, the brake output switches once the velocity
+        /// falls below this threshold, and the drive keeps the motor energized for
+        /// the brake lock delay (`BrakeConfig::lock_delay`) so the brake closes
+        /// before torque is removed. On a vertical axis, keep the threshold low
+        /// and the delay longer than the brake's closing time.
+        pub async fn set_brake_threshold_rpm(&mut self, rpm: u16) -> Result<()> {
+            common::check_brake_threshold(rpm)?;
+            self.write_register(registers::THRESHOLD_BRAKE, rpm).await
+        }
+
+        /// Get the velocity below which the brake engages (RPM)
+        pub async fn get_brake_threshold_rpm(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::THRESHOLD_BRAKE, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Enable or disable homing on power up
+        pub async fn homing_power_up_control(&mut self, enable: bool) -> Result<()> {
+            let mut reg = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+            if enable {
+                reg |= 1 << 2;
+            } else {
+                reg &= !(1 << 2);
+            }
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, reg).await
+        }
+
+        /// Configure CTRG trigger type (0: Bit0, 1: Level Trigger)
+        pub async fn set_ctrg_trigger_type(&mut self, level_trigger: bool) -> Result<()> {
+            let mut reg = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?[0];
+            if level_trigger {
+                reg |= 1 << 4;
+            } else {
+                reg &= !(1 << 4);
+            }
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, reg).await
+        }
+
+        /// Read all `PR_GLOBAL_CTRL_FCT` flags at once
+        pub async fn get_global_control_flags(&mut self) -> Result<GlobalControlFlags> {
+            let data = self.read_registers(registers::PR_GLOBAL_CTRL_FCT, 1).await?;
+            Ok(GlobalControlFlags::from(data[0]))
+        }
+
+        /// Write all `PR_GLOBAL_CTRL_FCT` flags in a single write
+        ///
+        /// Bits not covered by `GlobalControlFlags` are cleared.
+        pub async fn set_global_control_flags(&mut self, ctrl_flags: GlobalControlFlags) -> Result<()> {
+            self.write_register(registers::PR_GLOBAL_CTRL_FCT, ctrl_flags.into()).await
+        }
+
+        /// Configure homing parameters
+        pub async fn configure_homing(
+            &mut self,
+            direction: Direction,
+            move_to_pos: bool,
+            method: HomingMethod,
+        ) -> Result<()> {
+            let config = direction.to_bit()
+                + if move_to_pos { 0x0002 } else { 0x0000 } 
+                + u16::from(method);
+            self.write_register(registers::HOME_MODE, config).await?;
+            self.write_register(0x601A, 0x0002).await  // Additional configuration
+        }
+
+        /// Set homing switch position
+        pub async fn set_homing_position(&mut self, position: u32) -> Result<()> {
+            let [msb, lsb] = common::split_words(position);
+            self.write_registers(registers::HOME_SWITCH_POS_HIGH, &[msb, lsb]).await
+        }
+
+        /// Set homing switch position as a signed value
+        pub async fn set_homing_position_signed(&mut self, position: i32) -> Result<()> {
+            let [msb, lsb] = position_words(position);
+            self.write_registers(registers::HOME_SWITCH_POS_HIGH, &[msb, lsb]).await
+        }
+
+        /// Set homing stop position
+        pub async fn set_homing_stop_position(&mut self, position: u32) -> Result<()> {
+            let [msb, lsb] = common::split_words(position);
+            self.write_registers(registers::HOMING_STOP_POS_HIGH, &[msb, lsb]).await
+        }
+
+        /// Set homing stop position as a signed value, e.g. below the home switch
+        pub async fn set_homing_stop_position_signed(&mut self, position: i32) -> Result<()> {
+            let [msb, lsb] = position_words(position);
+            self.write_registers(registers::HOMING_STOP_POS_HIGH, &[msb, lsb]).await
+        }
+
+        /// Set homing high velocity (RPM)
+        pub async fn set_homing_high_velocity(&mut self, rpm: u16) -> Result<()> {
+            self.write_register(registers::HOMING_HIGH_VELOCITY, rpm).await
+        }
+
+        /// Set homing low velocity (RPM)
+        pub async fn set_homing_low_velocity(&mut self, rpm: u16) -> Result<()> {
+            self.write_register(registers::HOMING_LOW_VELOCITY, rpm).await
+        }
+
+        /// Set homing acceleration
+        pub async fn set_homing_acceleration(&mut self, acc: u16) -> Result<()> {
+            self.write_register(registers::HOMING_ACC, acc).await
+        }
+
+        /// Set homing acceleration as the time to reach the homing high velocity
+        ///
+        /// The register is in ms/1000rpm, like path ramps: the value is computed
+        /// from `ms` and the high velocity currently set on the drive, so set the
+        /// velocity first. Fails if the velocity is 0 or the result does not fit
+        /// the register.
+        pub async fn set_homing_acceleration_ms(&mut self, ms: u32) -> Result<()> {
+            let rpm = self.read_registers(registers::HOMING_HIGH_VELOCITY, 1).await?[0];
+            if rpm == 0 {
+                return Err(Em2rsError::InvalidParameter("homing high velocity is 0".into()));
+            }
+            let acc = (u64::from(ms) * 1000).div_ceil(u64::from(rpm));
+            let acc = u16::try_from(acc).ok().filter(|&acc| acc > 0).ok_or_else(|| {
+                Em2rsError::InvalidParameter(format!(
+                    "{} ms to {} rpm gives homing acceleration {} ms/1000rpm, must be 1-65535",
+                    ms, rpm, acc
+                ))
+            })?;
+            self.set_homing_acceleration(acc).await
+        }
+
+        /// Set homing deceleration
+        pub async fn set_homing_deceleration(&mut self, dec: u16) -> Result<()> {
+            self.write_register(registers::HOMING_DEC, dec).await
+        }
+
+        /// Apply complete homing configuration
+        ///
+        /// The stop position is only written when `move_to_pos_after` is set:
+        /// some firmware moves to a stop position left in `HOMING_STOP_POS_*` even
+        /// when the move after homing is disabled, so it is left untouched then.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, HomingConfig, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        ///
+        /// let moving = HomingConfig { move_to_pos_after: true, position_stop: 500, ..Default::default() };
+        /// client.apply_homing_config(&moving).await.unwrap();
+        /// assert_eq!(drive.get(registers::HOMING_STOP_POS_LOW), Some(500));
+        ///
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// let staying = HomingConfig { move_to_pos_after: false, position_stop: 500, ..Default::default() };
+        /// client.apply_homing_config(&staying).await.unwrap();
+        /// assert!(drive.writes().iter().all(|(addr, _)| *addr != registers::HOMING_STOP_POS_HIGH));
+        /// assert_eq!(drive.get(registers::HOMING_STOP_POS_LOW), None);
+        /// # });
+        /// ```
+        pub async fn apply_homing_config(&mut self, config: &HomingConfig) -> Result<()> {
+            self.configure_input(config.input_no, config.function, config.normally_closed).await?;
+            self.configure_homing(config.direction, config.move_to_pos_after, config.method).await?;
+            self.set_homing_position(config.position).await?;
+            if config.move_to_pos_after {
+                self.set_homing_stop_position(config.position_stop).await?;
+            }
+            self.set_homing_high_velocity(config.high_velocity).await?;
+            self.set_homing_low_velocity(config.low_velocity).await?;
+            self.set_homing_acceleration(config.acceleration).await?;
+            self.set_homing_deceleration(config.deceleration).await?;
+            Ok(())
+        }
+
+        /// Apply a homing configuration and matching soft limits
+        ///
+        /// Soft limits are relative to the zero set by homing, so both are
+        /// checked together before anything is written: `min` must be below
+        /// `max`, and the homing position, plus the stop position when the motor
+        /// moves there after homing, must lie within `min..=max`. Positions are
+        /// read as signed, like the drive's position counter.
+        pub async fn configure_referencing(&mut self, homing: &HomingConfig, min: i32, max: i32) -> Result<()> {
+            if min >= max {
+                return Err(Em2rsError::InvalidParameter(format!(
+                    "soft limit min {} must be below max {}",
+                    min, max
+                )));
+            }
+            let mut datums = vec![("homing position", homing.position as i32)];
+            if homing.move_to_pos_after {
+                datums.push(("homing stop position", homing.position_stop as i32));
+            }
+            for (name, pos) in datums {
+                if !(min..=max).contains(&pos) {
+                    return Err(Em2rsError::InvalidParameter(format!(
+                        "{} {} is outside soft limits {}..={}",
+                        name, pos, min, max
+                    )));
+                }
+            }
+            self.apply_homing_config(homing).await?;
+            self.apply_soft_limits(SoftLimits { min: min as u32, max: max as u32 }).await
+        }
+
+        /// Read the homing configuration back from the drive
+        ///
+        /// The homing registers are read in one request. The input fields come
+        /// from the first digital input mapped to a function used by the homing
+        /// method (`Org` for a home switch, `Pot` or `Not` for a limit switch),
+        /// and keep their `HomingConfig::default()` values if none is mapped.
+        pub async fn get_homing_config(&mut self) -> Result<HomingConfig> {
+            let count = registers::HOMING_DEC - registers::HOME_MODE + 1;
+            let data = self.read_registers(registers::HOME_MODE, count).await?;
+            let at = |addr: u16| data[(addr - registers::HOME_MODE) as usize];
+            let word = |h: u16, l: u16| common::join_words(at(h), at(l));
+            let (direction, move_to_pos_after, method) = decode_home_mode(at(registers::HOME_MODE));
+
+            let mut config = HomingConfig {
+                direction,
+                move_to_pos_after,
+                method,
+                position: word(registers::HOME_SWITCH_POS_HIGH, registers::HOME_SWITCH_POS_LOW),
+                position_stop: word(registers::HOMING_STOP_POS_HIGH, registers::HOMING_STOP_POS_LOW),
+                high_velocity: at(registers::HOMING_HIGH_VELOCITY),
+                low_velocity: at(registers::HOMING_LOW_VELOCITY),
+                acceleration: at(registers::HOMING_ACC),
+                deceleration: at(registers::HOMING_DEC),
+                ..HomingConfig::default()
+            };
+
+            let wanted: &[DigitalInputFunction] = match method {
+                HomingMethod::HomeSwitch => &[DigitalInputFunction::Org],
+                HomingMethod::LimitSwitch => &[DigitalInputFunction::Pot, DigitalInputFunction::Not],
+            };
+            let inputs = self.read_registers(registers::SI1, registers::SI7 - registers::SI1 + 1).await?;
+            for (i, &reg) in inputs.iter().step_by(2).enumerate() {
+                let function = reg & !flags::SI_NC_INCR;
+                if let Some(&function) = wanted.iter().find(|&&f| u16::from(f) == function) {
+                    config.input_no = i as u8 + 1;
+                    config.function = function;
+                    config.normally_closed = reg & flags::SI_NC_INCR != 0;
+                    break;
+                }
+            }
+            Ok(config)
+        }
+
+        /// Start homing sequence
+        pub async fn start_homing(&mut self) -> Result<()> {
+            self.set_pr_control(PrControlCommand::Homing).await
+        }
+
+        /// Start a path (0-8)
+        ///
+        /// In strict mode, the path is read first and an unconfigured path (motion
+        /// type `NoAction`, or a move with zero velocity) is rejected instead of
+        /// being silently ignored by the drive.
+        pub async fn start_path(&mut self, path_id: u8) -> Result<()> {
+            if path_id > 8 {
+                return Err(Em2rsError::InvalidPath(path_id));
+            }
+            if self.strict {
+                self.check_path_runnable(path_id).await?;
+            }
+            let command_value = u16::from(PrControlCommand::RunThePath) + path_id as u16;
+            self.write_command(registers::PR_CTRL, command_value).await
+        }
+
+        /// Start a path that overrides the move in progress
+        ///
+        /// Sets the interrupt bit of the path's control word (see
+        /// `configure_path_motion`) so the drive aborts the current move and runs
+        /// this path without a stop in between, e.g. to retarget mid-move. The
+        /// previous control word is written back once the path is triggered, even
+        /// if starting it failed, so a later `start_path` runs the path as
+        /// configured; a path configured with the interrupt bit keeps it.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// let ctrl = registers::PATH1_BASE + registers::PATH_CTRL_OFFSET;
+        /// drive.set(ctrl, 0x0001);
+        /// client.interrupt_with_path(1).await.unwrap();
+        /// assert_eq!(drive.writes(), [
+        ///     (ctrl, vec![0x0011]),
+        ///     (registers::PR_CTRL, vec![0x0011]),
+        ///     (ctrl, vec![0x0001]),
+        /// ]);
+        /// # });
+        /// ```
+        pub async fn interrupt_with_path(&mut self, path_id: u8) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let addr = base + registers::PATH_CTRL_OFFSET;
+            let ctrl = self.read_registers(addr, 1).await?[0];
+            if ctrl & 0x0010 != 0 {
+                return self.start_path(path_id).await;
+            }
+            self.write_register(addr, ctrl | 0x0010).await?;
+            let started = self.start_path(path_id).await;
+            let restored = self.write_register(addr, ctrl).await;
+            started.and(restored)
+        }
+
+        /// Quick stop the motor
+        ///
+        /// Decelerates with the configured ramp and keeps the motor enabled and
+        /// holding. See `emergency_stop` to also cut motor power.
+        pub async fn stop_motor(&mut self) -> Result<()> {
+            self.set_pr_control(PrControlCommand::QuickStop).await
+        }
+
+        /// Stop the motor and disable it immediately
+        ///
+        /// The drive has no emergency stop command over Modbus:
+        /// `DigitalInputFunction::Emergency` only exists on a wired input. This
+        /// sends the quick stop command, then clears `FORCED_ENA` (and the enable
+        /// bit of `RS485_CONTROL_WORD` with `EnableMethod::Rs485ControlWord`) right
+        /// away instead of waiting for the ramp. Unlike `stop_motor`, the motor is
+        /// left unpowered: it no longer holds its position, a moving load may coast,
+        /// and it must be enabled again, e.g. with `init`, before the next move.
+        /// Every write is attempted even if an earlier one fails, and the first
+        /// error is returned.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, EnableMethod, StepperConfig};
+        /// use tokio_modbus::ExceptionCode;
+        /// let drive = MemoryDrive::new();
+        /// let config = StepperConfig::new(1, 10000).with_enable_method(EnableMethod::Rs485ControlWord);
+        /// let mut client = Em2rsClient::new(drive.context(), config.clone());
+        /// client.emergency_stop().await.unwrap();
+        /// let order: Vec<u16> = drive.writes().iter().map(|(addr, _)| *addr).collect();
+        /// assert_eq!(
+        ///     order,
+        ///     [registers::PR_CTRL, registers::FORCED_ENA, registers::RS485_CONTROL_WORD]
+        /// );
+        ///
+        /// // The control word is still cleared when `FORCED_ENA` cannot be written
+        /// let drive = MemoryDrive::new();
+        /// drive.fail_at(registers::FORCED_ENA, ExceptionCode::ServerDeviceFailure);
+        /// let mut client = Em2rsClient::new(drive.context(), config);
+        /// let err = client.emergency_stop().await.unwrap_err();
+        /// assert_eq!(err.register(), Some(registers::FORCED_ENA));
+        /// assert_eq!(drive.writes().last(), Some(&(registers::RS485_CONTROL_WORD, vec![0])));
+        /// # });
+        /// ```
+        pub async fn emergency_stop(&mut self) -> Result<()> {
+            let stop = self.set_pr_control(PrControlCommand::QuickStop).await;
+            let forced = self.forced_enable_by_software(false).await;
+            let control_word = if self.config.enable_method == EnableMethod::Rs485ControlWord {
+                self.enable_via_control_word(false).await
+            } else {
+                Ok(())
+            };
+            stop.and(forced).and(control_word)
+        }
+
+        /// Quick stop the motor and wait until it reaches standstill
+        ///
+        /// Polls the motion status until the running flag clears and returns the
+        /// time the motor took to decelerate. Fails with `Em2rsError::Timeout` if
+        /// the motor is still running after `timeout`.
+        pub async fn stop_and_wait(&mut self, timeout: Duration) -> Result<Duration> {
+            self.stop_motor().await?;
+            let start = Instant::now();
+            while self.get_motion_status().await?.is_running() {
+                if start.elapsed() >= timeout {
+                    return Err(Em2rsError::Timeout(timeout));
+                }
+                B::sleep(STATUS_POLL_INTERVAL).await;
+            }
+            Ok(start.elapsed())
+        }
+
+        /// Set current position as zero
+        pub async fn manual_zero(&mut self) -> Result<()> {
+            self.set_pr_control(PrControlCommand::ManualZero).await
+        }
+
+        /// Bring the motor back to a known state after a fault
+        ///
+        /// Quick-stops the motor, clears the current alarm and starts the homing
+        /// sequence. The EM2RS runs open loop and has no position-error register to
+        /// clear: after a collision or lost steps the only way to realign the
+        /// position count with the mechanism is to re-home. Recommended flow after a
+        /// position-related fault:
+        /// 1. Remove the cause of the fault
+        /// 2. Call this method
+        /// 3. Poll `is_homing_completed` before commanding any path
+        ///
+        /// Homing must have been configured beforehand (see `apply_homing_config`).
+        pub async fn reset_to_safe_state(&mut self) -> Result<()> {
+            self.stop_motor().await?;
+            self.reset_current_alarm().await?;
+            self.start_homing().await
+        }
+
+        /// Configure path motion parameters
+        /// 
+        /// For simpler usage, consider using `apply_path_config` with a `PathConfig` struct
+        #[allow(clippy::too_many_arguments)]
+        pub async fn configure_path_motion(
+            &mut self,
+            path_id: u8,
+            motion_type: PathMotionType,
+            interrupt: bool,
+            overlap: bool,
+            absolute: bool,
+            jump: bool,
+            jump_to: u8,
+        ) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+
+            let mut config = u16::from(motion_type)
+                + if interrupt { 0x0010 } else { 0x0000 }
+                + if overlap { 0x0020 } else { 0x0000 }
+                + if absolute { 0x0000 } else { 0x0040 };
+
+            if jump {
+                config += 0x4000 + (((jump_to & 0x0F) as u16) << 8);
+            }
+
+            self.write_register(base, config).await
+        }
+
+        /// Set path position (32-bit)
+        ///
+        /// Like every 32-bit setter, both words are written in a single request so
+        /// the drive never latches a half-updated value.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// client.set_path_position(0, 0x0001_86A0).await.unwrap();
+        /// // One write multiple registers request (FC16) carrying both words
+        /// let high = registers::PATH0_BASE + registers::PATH_POSITION_H_OFFSET;
+        /// assert_eq!(drive.writes(), [(high, vec![0x0001, 0x86A0])]);
+        /// # });
+        /// ```
+        pub async fn set_path_position(&mut self, path_id: u8, position: u32) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let [msb, lsb] = common::split_words(position);
+            self.write_registers(base + registers::PATH_POSITION_H_OFFSET, &[msb, lsb]).await
+        }
+
+        /// Set path position as a signed value
+        ///
+        /// Use this for negative targets and relative moves in the negative
+        /// direction; `set_path_position` takes the raw 32-bit value.
+        pub async fn set_path_position_signed(&mut self, path_id: u8, position: i32) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let [msb, lsb] = position_words(position);
+            self.write_registers(base + registers::PATH_POSITION_H_OFFSET, &[msb, lsb]).await
+        }
+
+        /// Set path velocity (RPM)
+        pub async fn set_path_velocity(&mut self, path_id: u8, rpm: u16) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            self.write_register(base + registers::PATH_VELOCITY_OFFSET, rpm).await
+        }
+
+        /// Set path acceleration (ms/1000rpm)
+        pub async fn set_path_acceleration(&mut self, path_id: u8, acc: u16) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            self.write_register(base + registers::PATH_ACC_OFFSET, acc).await
+        }
+
+        /// Set path deceleration (ms/1000rpm)
+        pub async fn set_path_deceleration(&mut self, path_id: u8, dec: u16) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            self.write_register(base + registers::PATH_DEC_OFFSET, dec).await
+        }
+
+        /// Set path pause time (ms)
+        pub async fn set_path_pause_time(&mut self, path_id: u8, ms: u16) -> Result<()> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            self.write_register(base + registers::PATH_PAUSE_TIME_OFFSET, ms).await
+        }
+
+        /// Get the raw path control word (motion type and flags)
+        pub async fn get_path_control(&mut self, path_id: u8) -> Result<u16> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_CTRL_OFFSET, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Get path position (32-bit)
+        pub async fn get_path_position(&mut self, path_id: u8) -> Result<u32> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_POSITION_H_OFFSET, 2).await?;
+            Ok(common::join_words(data[0], data[1]))
+        }
+
+        /// Get a path's target position as a signed value
+        ///
+        /// Same registers as `get_path_position`, decoded as two's complement like
+        /// the position counter, e.g. to check a target before running the path.
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{test_util::MemoryDrive, Em2rsClient, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// for target in [0, 5000, -5000, -1, i32::MIN, i32::MAX] {
+        ///     client.set_path_position_signed(3, target).await.unwrap();
+        ///     assert_eq!(client.get_path_target(3).await.unwrap(), target);
+        /// }
+        /// # });
+        /// ```
+        pub async fn get_path_target(&mut self, path_id: u8) -> Result<i32> {
+            Ok(self.get_path_position(path_id).await? as i32)
+        }
+
+        /// Get path velocity (RPM)
+        pub async fn get_path_velocity(&mut self, path_id: u8) -> Result<u16> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_VELOCITY_OFFSET, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Get path acceleration (ms/1000rpm)
+        pub async fn get_path_acceleration(&mut self, path_id: u8) -> Result<u16> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_ACC_OFFSET, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Get path deceleration (ms/1000rpm)
+        pub async fn get_path_deceleration(&mut self, path_id: u8) -> Result<u16> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_DEC_OFFSET, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Get path pause time (ms)
+        pub async fn get_path_pause_time(&mut self, path_id: u8) -> Result<u16> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let data = self.read_registers(base + registers::PATH_PAUSE_TIME_OFFSET, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Read a path's configuration back in a single read
+        ///
+        /// Returns `None` if the path's motion type is `NoAction`.
+        pub async fn read_path_config(&mut self, path_id: u8) -> Result<Option<PathConfig>> {
+            let base = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+            let block = self.read_registers(base, registers::PATH_SPECIAL_PARAM_OFFSET + 1).await?;
+            Ok(PathConfig::from_block(path_id, &block))
+        }
+
+        /// Read all nine paths, skipping those whose motion type is `NoAction`
+        pub async fn read_all_paths(&mut self) -> Result<Vec<PathConfig>> {
+            let mut paths = Vec::new();
+            for path_id in 0..=8 {
+                if let Some(path) = self.read_path_config(path_id).await? {
+                    paths.push(path);
+                }
+            }
+            Ok(paths)
+        }
+
+        /// Apply complete path configuration
+        ///
+        /// The ramps are validated first, see [`PathConfig::check_ramps`].
+        ///
+        /// ```
+        /// # #[cfg(feature = "test-util")]
+        /// # tokio_test::block_on(async {
+        /// use em2rs::{registers, test_util::MemoryDrive, Em2rsClient, PathConfig, PathMotionType, StepperConfig};
+        /// let drive = MemoryDrive::new();
+        /// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+        /// let path = PathConfig::new(0).unwrap()
+        ///     .with_motion_type(PathMotionType::VelocityMovement)
+        ///     .with_position(5000)
+        ///     .with_velocity(300);
+        /// client.apply_path_config(&path).await.unwrap();
+        /// let base = registers::PATH0_BASE;
+        /// assert_eq!(drive.get(base + registers::PATH_CTRL_OFFSET), Some(0x0002));
+        /// assert_eq!(drive.get(base + registers::PATH_POSITION_L_OFFSET), None);
+        /// assert_eq!(drive.get(base + registers::PATH_VELOCITY_OFFSET), Some(300));
+        /// # });
+        /// ```
+        pub async fn apply_path_config(&mut self, config: &PathConfig) -> Result<()> {
+            config.check_ramps(self.strict)?;
+
+            self.configure_path_motion(
+                config.path_id,
+                config.motion_type,
+                false,
+                false,
+                config.absolute_position,
+                false,
+                0,
+            ).await?;
+
+            if config.motion_type != PathMotionType::VelocityMovement {
+                self.set_path_position(config.path_id, config.position).await?;
+            }
+            self.set_path_velocity(config.path_id, config.velocity).await?;
+            self.set_path_acceleration(config.path_id, config.acceleration).await?;
+            self.set_path_deceleration(config.path_id, config.deceleration).await?;
+
+            if config.pause_time > 0 {
+                self.set_path_pause_time(config.path_id, config.pause_time).await?;
+            }
+
+            Ok(())
+        }
+
+        /// Fingerprint of the drive's configuration for drift detection
+        ///
+        /// Reads every register range listed in `registers::FINGERPRINT_RANGES`,
+        /// then the nine path blocks at this client's [`PathLayout`], and returns
+        /// the 64-bit FNV-1a hash of each register's big-endian address followed
+        /// by its big-endian value, in that order. Store it after applying a
+        /// configuration and compare later: any change to these registers alters
+        /// the fingerprint.
+        pub async fn config_fingerprint(&mut self) -> Result<u64> {
+            let mut values = Vec::new();
+            for &(start, count) in registers::FINGERPRINT_RANGES {
+                let data = self.read_registers(start, count).await?;
+                values.extend((start..).zip(data));
+            }
+            for path_id in 0..=8 {
+                let start = self.path_layout.path_base(path_id).ok_or(Em2rsError::InvalidPath(path_id))?;
+                let data = self.read_registers(start, registers::PATH_SPECIAL_PARAM_OFFSET + 1).await?;
+                values.extend((start..).zip(data));
+            }
+            Ok(fingerprint_registers(values))
+        }
+
+        /// Get firmware version
+        ///
+        /// Version and firmware information are the only identification the drive
+        /// reports: there is no operating hours or power-on counter register, so
+        /// run time has to be tracked on the host.
+        pub async fn get_version(&mut self) -> Result<u16> {
+            let data = self.read_registers(registers::VERSION_INFORMATION, 1).await?;
+            Ok(data[0])
+        }
+
+        /// Fail with `Em2rsError::UnsupportedFirmware` if the firmware is older
+        /// than `min`
+        ///
+        /// Meant as a startup check for applications relying on features of
+        /// newer firmware. See [`FirmwareVersion`] for how versions compare.
+        pub async fn require_firmware(&mut self, min: FirmwareVersion) -> Result<()> {
+            let found = FirmwareVersion(self.get_version().await?);
+            if found < min {
+                return Err(Em2rsError::UnsupportedFirmware { found, required: min });
+            }
+            Ok(())
+        }
+
+        /// Get current alarm status
+        pub async fn get_current_alarm(&mut self) -> Result<CurrentAlarm> {
+            let data = self.read_registers(registers::CURRENT_ALARM, 1).await?;
+            Ok(CurrentAlarm(data[0]))
+        }
+
+        /// Fail with `Em2rsError::MotorFault` if any alarm is active
+        pub async fn error_if_alarmed(&mut self) -> Result<()> {
+            let alarm = self.get_current_alarm().await?;
+            if alarm.0 != 0 {
+                return Err(Em2rsError::MotorFault(alarm));
+            }
+            Ok(())
+        }
+    }
+}
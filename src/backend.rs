@@ -0,0 +1,98 @@
+//! Modbus transport behind the async and sync clients
+//!
+//! [`ClientCore`](crate::api::ClientCore) is written once against [`Backend`].
+//! The async context awaits each request; the sync context completes it before
+//! returning, so a core future driven by [`block_on`] is ready on first poll.
+
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+use tokio_modbus::prelude::*;
+
+/// Holding register access and waiting, as used by the clients
+pub(crate) trait Backend: SlaveContext + Send {
+    fn read_holding_registers(
+        &mut self,
+        addr: u16,
+        count: u16,
+    ) -> impl Future<Output = tokio_modbus::Result<Vec<u16>>> + Send;
+
+    fn write_single_register(
+        &mut self,
+        addr: u16,
+        value: u16,
+    ) -> impl Future<Output = tokio_modbus::Result<()>> + Send;
+
+    fn write_multiple_registers(
+        &mut self,
+        addr: u16,
+        values: &[u16],
+    ) -> impl Future<Output = tokio_modbus::Result<()>> + Send;
+
+    fn sleep(duration: Duration) -> impl Future<Output = ()> + Send;
+}
+
+impl Backend for client::Context {
+    async fn read_holding_registers(
+        &mut self,
+        addr: u16,
+        count: u16,
+    ) -> tokio_modbus::Result<Vec<u16>> {
+        Reader::read_holding_registers(self, addr, count).await
+    }
+
+    async fn write_single_register(&mut self, addr: u16, value: u16) -> tokio_modbus::Result<()> {
+        Writer::write_single_register(self, addr, value).await
+    }
+
+    async fn write_multiple_registers(
+        &mut self,
+        addr: u16,
+        values: &[u16],
+    ) -> tokio_modbus::Result<()> {
+        Writer::write_multiple_registers(self, addr, values).await
+    }
+
+    async fn sleep(duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+impl Backend for client::sync::Context {
+    async fn read_holding_registers(
+        &mut self,
+        addr: u16,
+        count: u16,
+    ) -> tokio_modbus::Result<Vec<u16>> {
+        SyncReader::read_holding_registers(self, addr, count)
+    }
+
+    async fn write_single_register(&mut self, addr: u16, value: u16) -> tokio_modbus::Result<()> {
+        SyncWriter::write_single_register(self, addr, value)
+    }
+
+    async fn write_multiple_registers(
+        &mut self,
+        addr: u16,
+        values: &[u16],
+    ) -> tokio_modbus::Result<()> {
+        SyncWriter::write_multiple_registers(self, addr, values)
+    }
+
+    async fn sleep(duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Run a client future on the sync backend to completion
+///
+/// Only for futures awaiting nothing but the sync backend, which never
+/// returns `Pending`.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    match pin!(future).poll(&mut TaskContext::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("sync backend request left pending"),
+    }
+}
@@ -0,0 +1,138 @@
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio_modbus::prelude::*;
+use crate::client::Em2rsClient;
+use crate::registers;
+use crate::sync::Em2rsSyncClient;
+use crate::types::*;
+
+/// Find the slave IDs that answer on a bus
+///
+/// Reads the firmware version from each ID of `range` in turn, waiting up to
+/// `timeout` per ID, and returns the IDs that answered, in order. An answer
+/// with a Modbus exception still counts, as with [`probe_baud`](crate::probe_baud);
+/// timeouts and transport errors only skip the ID. ID 0 is the broadcast
+/// address and never answers. Keep `timeout` above the bus round trip: a
+/// reply arriving after its timeout can be taken for the next ID's answer.
+/// The context is left addressing the last ID scanned.
+///
+/// ```
+/// # #[cfg(feature = "test-util")]
+/// # tokio_test::block_on(async {
+/// use std::time::Duration;
+/// use em2rs::{scan, test_util::MemoryDrive};
+/// let bus = MemoryDrive::new();
+/// bus.answer_only([2, 5]);
+/// let mut ctx = bus.context();
+/// let found = scan(&mut ctx, 1..=6, Duration::from_millis(10)).await;
+/// assert_eq!(found, [2, 5]);
+/// # });
+/// ```
+pub async fn scan(
+    ctx: &mut client::Context,
+    range: RangeInclusive<u8>,
+    timeout: Duration,
+) -> Vec<u8> {
+    let mut found = Vec::new();
+    for id in range {
+        ctx.set_slave(Slave::from(id));
+        let request = ctx.read_holding_registers(registers::VERSION_INFORMATION, 1);
+        if let Ok(Ok(_)) = tokio::time::timeout(timeout, request).await {
+            found.push(id);
+        }
+    }
+    found
+}
+
+/// Error for a bus whose context was not handed back
+fn context_lost() -> Em2rsError {
+    Em2rsError::OperationFailed("bus context lost by an interrupted with_motor call".into())
+}
+
+/// Several motors sharing one async Modbus context
+///
+/// Replaces threading the context through `Em2rsClient::into_context` and
+/// `Em2rsClient::new` by hand: [`with_motor`](Self::with_motor) builds a
+/// client for one drive, runs a closure against it and takes the context
+/// back, so a list of configurations can simply be looped over:
+///
+/// ```no_run
+/// # async fn example(mut bus: em2rs::Em2rsBus, configs: Vec<em2rs::StepperConfig>) -> em2rs::Result<()> {
+/// for config in &configs {
+///     bus.with_motor(config, |motor| Box::pin(async move { motor.init().await })).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Em2rsBus {
+    ctx: Option<client::Context>,
+}
+
+impl Em2rsBus {
+    /// Take ownership of the context shared by every motor on the bus
+    pub fn new(ctx: client::Context) -> Self {
+        Self { ctx: Some(ctx) }
+    }
+
+    /// Run `f` against the motor described by `config`
+    ///
+    /// Selects `config.slave_id` and gives `f` a client built with default
+    /// options. If the returned future is dropped before completing, the
+    /// context is lost and every later call fails with
+    /// `Em2rsError::OperationFailed`.
+    pub async fn with_motor<T, F>(&mut self, config: &StepperConfig, f: F) -> Result<T>
+    where
+        F: for<'a> FnOnce(&'a mut Em2rsClient) -> Pin<Box<dyn Future<Output = Result<T>> + 'a>>,
+    {
+        let mut ctx = self.ctx.take().ok_or_else(context_lost)?;
+        ctx.set_slave(Slave::from(config.slave_id));
+        let mut client = Em2rsClient::new(ctx, config.clone());
+        let result = f(&mut client).await;
+        self.ctx = Some(client.into_context());
+        result
+    }
+
+    /// Give the context back, `None` if it was lost
+    pub fn into_context(self) -> Option<client::Context> {
+        self.ctx
+    }
+}
+
+/// Several motors sharing one synchronous Modbus context
+///
+/// Blocking counterpart of [`Em2rsBus`].
+pub struct Em2rsSyncBus {
+    ctx: Option<client::sync::Context>,
+}
+
+impl Em2rsSyncBus {
+    /// Take ownership of the context shared by every motor on the bus
+    pub fn new(ctx: client::sync::Context) -> Self {
+        Self { ctx: Some(ctx) }
+    }
+
+    /// Run `f` against the motor described by `config`
+    ///
+    /// Selects `config.slave_id` and gives `f` a client built with default
+    /// options. If `f` panics, the context is lost and every later call fails
+    /// with `Em2rsError::OperationFailed`.
+    pub fn with_motor<T>(
+        &mut self,
+        config: &StepperConfig,
+        f: impl FnOnce(&mut Em2rsSyncClient) -> Result<T>,
+    ) -> Result<T> {
+        let mut ctx = self.ctx.take().ok_or_else(context_lost)?;
+        ctx.set_slave(Slave::from(config.slave_id));
+        let mut client = Em2rsSyncClient::new(ctx, config.clone());
+        let result = f(&mut client);
+        self.ctx = Some(client.into_context());
+        result
+    }
+
+    /// Give the context back, `None` if it was lost
+    pub fn into_context(self) -> Option<client::sync::Context> {
+        self.ctx
+    }
+}
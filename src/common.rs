@@ -0,0 +1,146 @@
+//! Bus-independent helpers for the client operations
+//!
+//! Validating arguments and encoding or decoding register values, for the
+//! operations in [`api`](crate::api) and for anything else in the crate that
+//! needs them without a client at hand.
+
+use std::time::Duration;
+use crate::registers;
+use crate::types::*;
+
+/// Check a Modbus slave ID is in the 1-247 unicast range
+pub(crate) fn check_slave_id(id: u8) -> Result<()> {
+    if !(1..=247).contains(&id) {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "slave ID {} out of range 1-247",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Check a pulses per revolution setting is in the 200-51200 range
+pub(crate) fn check_pulse_per_rev(pulse_per_rev: u16) -> Result<()> {
+    if !(200..=51200).contains(&pulse_per_rev) {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "pulse per revolution {} out of range 200-51200",
+            pulse_per_rev
+        )));
+    }
+    Ok(())
+}
+
+/// Check a jog velocity is in the 1-3000 RPM range
+pub(crate) fn check_jog_velocity(rpm: u16) -> Result<()> {
+    if !(1..=3000).contains(&rpm) {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "jog velocity {} rpm, must be 1-3000",
+            rpm
+        )));
+    }
+    Ok(())
+}
+
+/// Check a standby current percentage is at most 100
+pub(crate) fn check_standby_percent(percent: u16) -> Result<()> {
+    if percent > 100 {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "standby current {}% out of range 0-100",
+            percent
+        )));
+    }
+    Ok(())
+}
+
+/// Check a brake threshold is in the 0-3000 RPM range
+pub(crate) fn check_brake_threshold(rpm: u16) -> Result<()> {
+    if rpm > 3000 {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "brake threshold {} rpm out of range 0-3000",
+            rpm
+        )));
+    }
+    Ok(())
+}
+
+/// Check a register count fits in one read request (1-125)
+pub(crate) fn check_read_count(count: u16) -> Result<()> {
+    if !(1..=125).contains(&count) {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "{} registers to read, must be 1-125",
+            count
+        )));
+    }
+    Ok(())
+}
+
+/// Check a register count fits in one write request (1-123)
+pub(crate) fn check_write_count(count: usize) -> Result<()> {
+    if count == 0 || count > 123 {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "{} registers to write, must be 1-123",
+            count
+        )));
+    }
+    Ok(())
+}
+
+/// Check a digital input number is 1-7
+pub(crate) fn check_input_no(input_no: u8) -> Result<()> {
+    if !(1..=7).contains(&input_no) {
+        return Err(Em2rsError::InvalidDigitalInput(input_no));
+    }
+    Ok(())
+}
+
+/// Configuration register of a digital input (1-7)
+pub(crate) fn input_register(input_no: u8) -> Result<u16> {
+    check_input_no(input_no)?;
+    Ok(registers::SI1 + ((input_no - 1) as u16 * 2))
+}
+
+/// Configuration register of a digital output (1-3)
+pub(crate) fn output_register(output_no: u8) -> Result<u16> {
+    if !(1..=3).contains(&output_no) {
+        return Err(Em2rsError::InvalidDigitalOutput(output_no));
+    }
+    Ok(registers::SO1 + ((output_no - 1) as u16 * 2))
+}
+
+/// Peak current register value for a phase current
+///
+/// The product is truncated. Fails if it is negative, not finite or above
+/// what the 16-bit register holds, instead of letting the cast saturate.
+pub(crate) fn peak_current_raw(phase_current: f32, scale: f32) -> Result<u16> {
+    let peak = phase_current * scale;
+    if !(0.0..=f32::from(u16::MAX)).contains(&peak) {
+        return Err(Em2rsError::InvalidParameter(format!(
+            "phase current {} A gives peak current register {}, must be 0-65535",
+            phase_current, peak
+        )));
+    }
+    Ok(peak as u16)
+}
+
+/// Phase current for a peak current register value
+pub(crate) fn phase_current_from_raw(raw: u16, scale: f32) -> f32 {
+    raw as f32 / scale
+}
+
+/// Split a 32-bit value into its `[high, low]` register words
+pub(crate) fn split_words(value: u32) -> [u16; 2] {
+    [(value >> 16) as u16, value as u16]
+}
+
+/// Join `high` and `low` register words into a 32-bit value
+pub(crate) fn join_words(high: u16, low: u16) -> u32 {
+    ((high as u32) << 16) | low as u32
+}
+
+/// Delay after each request for a client built without `with_request_delay`
+///
+/// The inter-frame delay for `baud` with the `modbus-delay` feature, none
+/// without it.
+pub(crate) fn default_request_delay(baud: u32) -> Option<Duration> {
+    cfg!(feature = "modbus-delay").then(|| recommended_inter_frame_delay(baud))
+}
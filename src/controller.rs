@@ -0,0 +1,231 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use tokio::time::{sleep, Instant};
+use crate::client::Em2rsClient;
+use crate::types::*;
+
+/// Interval between motion status checks in [`move_pair`]
+const PAIR_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Object-safe interface over an EM2RS motor controller
+///
+/// This trait covers the common motion and status operations so that clients
+/// can be held behind a `Box<dyn StepperController>`, e.g. to manage a fleet of
+/// motors reached through different transports in a single collection.
+///
+/// # Example
+/// ```no_run
+/// use em2rs::{StepperController, Result};
+///
+/// async fn stop_all(motors: &mut [Box<dyn StepperController>]) -> Result<()> {
+///     for motor in motors.iter_mut() {
+///         motor.stop_motor().await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[async_trait]
+pub trait StepperController: Send {
+    /// Modbus slave ID of the controlled motor
+    fn slave_id(&self) -> u8;
+
+    /// Initialize the stepper motor with configured parameters
+    async fn init(&mut self) -> Result<()>;
+
+    /// Apply complete path configuration
+    async fn apply_path_config(&mut self, config: &PathConfig) -> Result<()>;
+
+    /// Apply complete homing configuration
+    async fn apply_homing_config(&mut self, config: &HomingConfig) -> Result<()>;
+
+    /// Start a path (0-8)
+    async fn start_path(&mut self, path_id: u8) -> Result<()>;
+
+    /// Start homing sequence
+    async fn start_homing(&mut self) -> Result<()>;
+
+    /// Quick stop the motor
+    async fn stop_motor(&mut self) -> Result<()>;
+
+    /// Stop the motor and disable it immediately
+    async fn emergency_stop(&mut self) -> Result<()>;
+
+    /// Set current position as zero
+    async fn manual_zero(&mut self) -> Result<()>;
+
+    /// Get motion status
+    async fn get_motion_status(&mut self) -> Result<MotionStatus>;
+
+    /// Get current alarm status
+    async fn get_current_alarm(&mut self) -> Result<CurrentAlarm>;
+
+    /// Get firmware version
+    async fn get_version(&mut self) -> Result<u16>;
+
+    /// Check if path is completed
+    async fn is_path_completed(&mut self) -> Result<bool> {
+        Ok(self.get_motion_status().await?.is_path_complete())
+    }
+
+    /// Check if homing is completed
+    async fn is_homing_completed(&mut self) -> Result<bool> {
+        Ok(self.get_motion_status().await?.is_homing_complete())
+    }
+}
+
+/// Move two coupled axes by the same relative distance
+///
+/// Configures `path_id` on both motors as a relative move of `delta` pulses at
+/// `velocity` RPM, starts both paths concurrently to minimize the delay between
+/// the two axes, then waits until both report path completion. Intended for
+/// gantries where staggered starts would rack the two sides. Fails with
+/// `Em2rsError::OperationFailed` if either axis reports a fault, and with
+/// `Em2rsError::Timeout` if both paths have not completed within `timeout`.
+///
+/// Once either path may have started, any failure, including a start that
+/// failed on one axis only, quick stops both axes before the error is
+/// returned, so one side never keeps moving alone.
+///
+/// ```
+/// # #[cfg(feature = "test-util")]
+/// # tokio_test::block_on(async {
+/// use std::time::Duration;
+/// use em2rs::registers::{self, flags};
+/// use em2rs::{move_pair, test_util::MemoryDrive, Em2rsClient, StepperConfig};
+/// use tokio_modbus::ExceptionCode;
+/// let quick_stop = (registers::PR_CTRL, vec![0x0040]);
+///
+/// // The right axis faults: both are stopped
+/// let (left_drive, right_drive) = (MemoryDrive::new(), MemoryDrive::new());
+/// let mut left = Em2rsClient::new(left_drive.context(), StepperConfig::new(1, 10000));
+/// let mut right = Em2rsClient::new(right_drive.context(), StepperConfig::new(2, 10000));
+/// right_drive.set(registers::MOTION_STATUS, flags::MS_FAULT);
+/// assert!(move_pair(&mut left, &mut right, 0, 2000, 300, Duration::from_secs(1)).await.is_err());
+/// assert_eq!(left_drive.writes().last(), Some(&quick_stop));
+/// assert_eq!(right_drive.writes().last(), Some(&quick_stop));
+///
+/// // The right axis fails to start: the left one is stopped
+/// let (left_drive, right_drive) = (MemoryDrive::new(), MemoryDrive::new());
+/// let mut left = Em2rsClient::new(left_drive.context(), StepperConfig::new(1, 10000));
+/// let mut right = Em2rsClient::new(right_drive.context(), StepperConfig::new(2, 10000));
+/// right_drive.fail_at(registers::PR_CTRL, ExceptionCode::ServerDeviceBusy);
+/// assert!(move_pair(&mut left, &mut right, 0, 2000, 300, Duration::from_secs(1)).await.is_err());
+/// assert_eq!(left_drive.writes().last(), Some(&quick_stop));
+/// # });
+/// ```
+pub async fn move_pair<A, B>(
+    a: &mut A,
+    b: &mut B,
+    path_id: u8,
+    delta: i32,
+    velocity: u16,
+    timeout: Duration,
+) -> Result<()>
+where
+    A: StepperController + ?Sized,
+    B: StepperController + ?Sized,
+{
+    let path = PathConfig::new(path_id)?
+        .relative()
+        .with_position(delta as u32)
+        .with_velocity(velocity);
+    a.apply_path_config(&path).await?;
+    b.apply_path_config(&path).await?;
+
+    let (a_started, b_started) = tokio::join!(a.start_path(path_id), b.start_path(path_id));
+    let result = match a_started.and(b_started) {
+        Ok(()) => wait_pair(a, b, timeout).await,
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let (a_stopped, b_stopped) = tokio::join!(a.stop_motor(), b.stop_motor());
+        for (id, stopped) in [(a.slave_id(), a_stopped), (b.slave_id(), b_stopped)] {
+            if let Err(e) = stopped {
+                log::warn!("slave {}: stop after failed paired move failed: {}", id, e);
+            }
+        }
+    }
+    result
+}
+
+/// Wait until both paths of [`move_pair`] complete, failing on a fault
+async fn wait_pair<A, B>(a: &mut A, b: &mut B, timeout: Duration) -> Result<()>
+where
+    A: StepperController + ?Sized,
+    B: StepperController + ?Sized,
+{
+    let start = Instant::now();
+    let (mut a_done, mut b_done) = (false, false);
+    loop {
+        let a_status = a.get_motion_status().await?;
+        let b_status = b.get_motion_status().await?;
+        for (id, status) in [(a.slave_id(), a_status), (b.slave_id(), b_status)] {
+            if status.is_fault() {
+                return Err(Em2rsError::OperationFailed(format!(
+                    "slave {} faulted during paired move",
+                    id
+                )));
+            }
+        }
+        a_done = a_done || a_status.is_path_complete();
+        b_done = b_done || b_status.is_path_complete();
+        if a_done && b_done {
+            return Ok(());
+        }
+        if start.elapsed() >= timeout {
+            return Err(Em2rsError::Timeout(timeout));
+        }
+        sleep(PAIR_POLL_INTERVAL).await;
+    }
+}
+
+#[async_trait]
+impl StepperController for Em2rsClient {
+    fn slave_id(&self) -> u8 {
+        Em2rsClient::slave_id(self)
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        Em2rsClient::init(self).await
+    }
+
+    async fn apply_path_config(&mut self, config: &PathConfig) -> Result<()> {
+        Em2rsClient::apply_path_config(self, config).await
+    }
+
+    async fn apply_homing_config(&mut self, config: &HomingConfig) -> Result<()> {
+        Em2rsClient::apply_homing_config(self, config).await
+    }
+
+    async fn start_path(&mut self, path_id: u8) -> Result<()> {
+        Em2rsClient::start_path(self, path_id).await
+    }
+
+    async fn start_homing(&mut self) -> Result<()> {
+        Em2rsClient::start_homing(self).await
+    }
+
+    async fn stop_motor(&mut self) -> Result<()> {
+        Em2rsClient::stop_motor(self).await
+    }
+
+    async fn emergency_stop(&mut self) -> Result<()> {
+        Em2rsClient::emergency_stop(self).await
+    }
+
+    async fn manual_zero(&mut self) -> Result<()> {
+        Em2rsClient::manual_zero(self).await
+    }
+
+    async fn get_motion_status(&mut self) -> Result<MotionStatus> {
+        Em2rsClient::get_motion_status(self).await
+    }
+
+    async fn get_current_alarm(&mut self) -> Result<CurrentAlarm> {
+        Em2rsClient::get_current_alarm(self).await
+    }
+
+    async fn get_version(&mut self) -> Result<u16> {
+        Em2rsClient::get_version(self).await
+    }
+}
@@ -6,8 +6,13 @@
 //! # Features
 //! - Async API using tokio-modbus
 //! - Synchronous wrapper for blocking contexts
-//! - Support for multiple motor instances on the same bus
+//! - Thread-safe [`Em2rsSharedClient`] for sharing one sync client across threads
+//! - Support for multiple motor instances on the same bus, see [`Em2rsBus`]
+//! - Object-safe [`StepperController`] trait for heterogeneous fleets
 //! - Complete register access and high-level operations
+//! - In-memory `MemoryDrive` for hardware-free tests (`test-util` feature)
+//! - Modbus TCP contexts for RS485-to-Ethernet gateways (`tcp` feature)
+//! - Serializable configuration types (`serde` feature)
 //!
 //! # Examples
 //!
@@ -52,9 +57,22 @@
 
 pub mod registers;
 pub mod types;
+mod common;
+mod backend;
+mod api;
 pub mod client;
 pub mod sync;
+pub mod shared;
+pub mod bus;
+pub mod controller;
+pub mod rotary;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
-pub use client::Em2rsClient;
-pub use sync::Em2rsSyncClient;
+pub use client::{Em2rsClient, FaultMonitor};
+pub use sync::{probe_baud, Em2rsSyncClient};
+pub use shared::Em2rsSharedClient;
+pub use bus::{scan, Em2rsBus, Em2rsSyncBus};
+pub use controller::{move_pair, StepperController};
+pub use rotary::RotaryAxis;
 pub use types::*;
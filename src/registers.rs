@@ -42,6 +42,7 @@ pub const MAX_STOP_TIME: u16 = 0x01A5;
 pub const AUTO_TUNING_POWER_ON: u16 = 0x01AB;
 
 // RS485 Configuration
+// Bus termination and biasing are hardware settings with no register.
 pub const RS485_BAUDRATE: u16 = 0x01BD;
 pub const RS485_ID: u16 = 0x01BF;
 pub const RS485_DATA_TYPE: u16 = 0x01C1;
@@ -63,6 +64,10 @@ pub const VERSION_INFORMATION: u16 = 0x01FF;
 pub const FIRMWARE_INFORMATION: u16 = 0x0201;
 
 // Motor Model and Advanced Parameters
+// The current loop gains and back-EMF coefficient are the only tuning
+// registers: the EM2RS has no velocity loop, so no velocity feedforward, and
+// no resonance notch filter. Use CMD_FILTER_TIME or change the velocity to
+// move away from a structural resonance.
 pub const MOTOR_MODEL: u16 = 0x0231;
 pub const BACK_EMF_COEF: u16 = 0x0235;
 pub const CURRENT_LOOP_PROPORTIONAL_KP: u16 = 0x0237;
@@ -84,6 +89,9 @@ pub const SOFT_LIMIT_P_H: u16 = 0x6006;
 pub const SOFT_LIMIT_P_L: u16 = 0x6007;
 pub const SOFT_LIMIT_N_H: u16 = 0x6008;
 pub const SOFT_LIMIT_N_L: u16 = 0x6009;
+/// Signed 32-bit commanded position counter, high word
+pub const CMD_POSITION_H: u16 = 0x602A;
+pub const CMD_POSITION_L: u16 = 0x602B;
 
 // Homing Configuration
 pub const HOME_MODE: u16 = 0x600A;
@@ -117,6 +125,26 @@ pub const PATH_DEC_OFFSET: u16 = 5;
 pub const PATH_PAUSE_TIME_OFFSET: u16 = 6;
 pub const PATH_SPECIAL_PARAM_OFFSET: u16 = 7;
 
+/// Register ranges `(start, count)` hashed by `config_fingerprint`, in order
+///
+/// Covers the basic motor parameters, command filter, digital input and output
+/// mapping, brake settings, peak current, PR global control, soft limits and
+/// homing. The path blocks are hashed after these ranges, at the
+/// client's `PathLayout`.
+pub const FINGERPRINT_RANGES: &[(u16, u16)] = &[
+    (PULSE_PER_REV, 1),
+    (MOTOR_DIRECTION, 1),
+    (MOTOR_INDUCTANCE, 1),
+    (CMD_FILTER_TIME, 1),
+    (SI1, SI7 - SI1 + 1),
+    (SO1, SO3 - SO1 + 1),
+    (DELAY_BRAKE_RELEASED, THRESHOLD_BRAKE - DELAY_BRAKE_RELEASED + 1),
+    (PEAK_CURRENT, 1),
+    (PR_GLOBAL_CTRL_FCT, 1),
+    (SOFT_LIMIT_P_H, SOFT_LIMIT_N_L - SOFT_LIMIT_P_H + 1),
+    (HOME_MODE, HOMING_DEC - HOME_MODE + 1),
+];
+
 /// Bit flags and increments
 pub mod flags {
     // Digital input normally closed increment
@@ -132,9 +160,21 @@ pub mod flags {
     pub const MS_CMD_COMPLETE: u16 = 0x0010;
     pub const MS_PATH_COMPLETE: u16 = 0x0020;
     pub const MS_HOMING_COMPLETE: u16 = 0x0040;
+
+    // PR global control function flags
+    pub const PR_CTRG_DOUBLE_EDGE: u16 = 0x0001;
+    pub const PR_SOFT_LIMIT_ENABLE: u16 = 0x0002;
+    pub const PR_HOMING_POWER_UP: u16 = 0x0004;
+    pub const PR_CTRG_LEVEL_TRIGGER: u16 = 0x0010;
+
+    // RS485 control word flags
+    pub const RS485_CW_ENABLE: u16 = 0x0001;
 }
 
 /// Helper function to get path base register
+///
+/// Uses the documented layout; clients address paths through their
+/// `PathLayout`, see `with_path_layout`.
 pub const fn get_path_base(path_id: u8) -> Option<u16> {
     match path_id {
         0 => Some(PATH0_BASE),
@@ -0,0 +1,103 @@
+use crate::client::Em2rsClient;
+use crate::types::*;
+
+/// Rotary axis helper working in degrees modulo one revolution
+///
+/// Wraps an async client for dial or turntable applications: positions are
+/// taken modulo `pulse_per_rev` and moves go the shortest way around. Moves
+/// are run as relative moves on a single path slot (path 0 at 100 RPM unless
+/// changed with [`with_path`](Self::with_path) and
+/// [`with_velocity`](Self::with_velocity)).
+pub struct RotaryAxis {
+    client: Em2rsClient,
+    pulse_per_rev: u16,
+    path_id: u8,
+    velocity: u16,
+}
+
+impl RotaryAxis {
+    /// Wrap a client whose drive is set to `pulse_per_rev` pulses per revolution
+    ///
+    /// Fails with `Em2rsError::InvalidParameter` if `pulse_per_rev` is 0.
+    ///
+    /// ```
+    /// # #[cfg(feature = "test-util")]
+    /// # {
+    /// use em2rs::{test_util::MemoryDrive, Em2rsClient, Em2rsError, RotaryAxis, StepperConfig};
+    ///
+    /// let client = Em2rsClient::new(MemoryDrive::new().context(), StepperConfig::new(1, 10000));
+    /// assert!(matches!(RotaryAxis::new(client, 0), Err(Em2rsError::InvalidParameter(_))));
+    /// # }
+    /// ```
+    pub fn new(client: Em2rsClient, pulse_per_rev: u16) -> Result<Self> {
+        if pulse_per_rev == 0 {
+            return Err(Em2rsError::InvalidParameter("pulse per revolution is 0".into()));
+        }
+        Ok(Self {
+            client,
+            pulse_per_rev,
+            path_id: 0,
+            velocity: 100,
+        })
+    }
+
+    /// Path slot used for moves (0-8)
+    pub fn with_path(mut self, path_id: u8) -> Self {
+        self.path_id = path_id;
+        self
+    }
+
+    /// Velocity used for moves (RPM)
+    pub fn with_velocity(mut self, rpm: u16) -> Self {
+        self.velocity = rpm;
+        self
+    }
+
+    /// Access the wrapped client
+    pub fn client_mut(&mut self) -> &mut Em2rsClient {
+        &mut self.client
+    }
+
+    /// Consume the axis and return the wrapped client
+    pub fn into_inner(self) -> Em2rsClient {
+        self.client
+    }
+
+    /// Current angle in degrees, in `[0, 360)`
+    pub async fn get_angle(&mut self) -> Result<f64> {
+        let ppr = i64::from(self.pulse_per_rev);
+        let counts = i64::from(self.client.get_current_position().await?).rem_euclid(ppr);
+        Ok(counts as f64 * 360.0 / ppr as f64)
+    }
+
+    /// Start a move to an absolute angle along the shortest way around
+    ///
+    /// `degrees` may be any value and is wrapped to one revolution. Returns the
+    /// relative move in pulses, negative when turning backwards. The move is
+    /// started but not awaited: poll `is_path_completed` on the client.
+    pub async fn move_to_angle(&mut self, degrees: f64) -> Result<i32> {
+        let ppr = i64::from(self.pulse_per_rev);
+        let current = i64::from(self.client.get_current_position().await?).rem_euclid(ppr);
+        let target = ((degrees.rem_euclid(360.0) / 360.0 * ppr as f64).round() as i64).rem_euclid(ppr);
+        let delta = shortest_delta(current, target, ppr) as i32;
+
+        let path = PathConfig::new(self.path_id)?
+            .relative()
+            .with_position(delta as u32)
+            .with_velocity(self.velocity);
+        self.client.apply_path_config(&path).await?;
+        self.client.start_path(self.path_id).await?;
+        Ok(delta)
+    }
+}
+
+/// Signed move from `current` to `target` counts, both in `[0, ppr)`, taking
+/// the shorter way around
+fn shortest_delta(current: i64, target: i64, ppr: i64) -> i64 {
+    let delta = (target - current).rem_euclid(ppr);
+    if delta > ppr / 2 {
+        delta - ppr
+    } else {
+        delta
+    }
+}
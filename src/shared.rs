@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use crate::sync::Em2rsSyncClient;
+use crate::types::*;
+
+/// Generate methods forwarding to the locked `Em2rsSyncClient`
+macro_rules! delegate {
+    ($( fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty; )*) => {
+        $(
+            #[doc = concat!("Locking counterpart of [`Em2rsSyncClient::", stringify!($name), "`]")]
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.lock().$name($($arg),*)
+            }
+        )*
+    };
+}
+
+/// Thread-safe handle to a synchronous EM2RS client
+///
+/// `Em2rsSyncClient` is `Send` but not `Sync`: it can be moved to another
+/// thread but not used from several threads at once. `Em2rsSharedClient` wraps
+/// it in an `Arc<Mutex<_>>` and exposes the same methods taking `&self`, each
+/// locking the client for the duration of the call. Clones share the same
+/// client, so one handle can be given to every thread touching the bus.
+///
+/// Waiting methods such as `stop_and_wait` hold the lock until they return.
+/// Use [`lock`](Self::lock) to run several calls without interleaving.
+#[derive(Clone)]
+pub struct Em2rsSharedClient {
+    inner: Arc<Mutex<Em2rsSyncClient>>,
+}
+
+impl Em2rsSharedClient {
+    /// Wrap a synchronous client for shared access
+    pub fn new(client: Em2rsSyncClient) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(client)),
+        }
+    }
+
+    /// Lock the client for a sequence of calls
+    ///
+    /// A poisoned lock is recovered: the client holds no invariant that a
+    /// panicking caller could break.
+    pub fn lock(&self) -> MutexGuard<'_, Em2rsSyncClient> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Modbus slave ID this client talks to
+    pub fn slave_id(&self) -> u8 {
+        self.lock().slave_id()
+    }
+
+    /// Copy of the client's current configuration
+    pub fn config(&self) -> StepperConfig {
+        self.lock().config().clone()
+    }
+
+    delegate! {
+        fn init(&self) -> Result<()>;
+        fn change_slave_id(&self, new_id: u8) -> Result<()>;
+        fn set_baudrate(&self, baud: Baudrate) -> Result<()>;
+        fn apply_machine_config(&self, machine: &MachineConfig) -> Result<()>;
+        fn dump_registers(&self, ranges: &[RangeInclusive<u16>]) -> Result<BTreeMap<u16, u16>>;
+        fn read_holding(&self, addr: u16, count: u16) -> Result<Vec<u16>>;
+        fn write_holding(&self, addr: u16, value: u16) -> Result<()>;
+        fn write_holding_block(&self, addr: u16, values: &[u16]) -> Result<()>;
+        fn write_register_verified(&self, addr: u16, value: u16) -> Result<()>;
+        fn set_pulse_per_rev(&self, pulse_per_rev: u16) -> Result<()>;
+        fn get_pulse_per_rev(&self) -> Result<u16>;
+        fn get_motor_model(&self) -> Result<u16>;
+        fn get_motor_direction(&self) -> Result<Direction>;
+        fn validate_directions(&self, homing: Direction) -> Result<()>;
+        fn set_peak_current(&self, phase_current: f32) -> Result<()>;
+        fn get_peak_current(&self) -> Result<f32>;
+        fn set_standby_current_percent(&self, percent: u16) -> Result<()>;
+        fn get_standby_current_percent(&self) -> Result<u16>;
+        fn set_standby_switching_time(&self, ms: u16) -> Result<()>;
+        fn get_standby_switching_time(&self) -> Result<u16>;
+        fn set_motor_inductance(&self, inductance: u16) -> Result<()>;
+        fn get_identified_inductance(&self) -> Result<u16>;
+        fn read_motor_params(&self) -> Result<MotorParams>;
+        fn write_motor_params(&self, params: &MotorParams) -> Result<()>;
+        fn apply_motion_smoothing(&self, smoothing: MotionSmoothing) -> Result<()>;
+        fn get_motion_smoothing(&self) -> Result<MotionSmoothing>;
+        fn forced_enable_by_software(&self, enable: bool) -> Result<()>;
+        fn enable_via_control_word(&self, enable: bool) -> Result<()>;
+        fn save_param_eeprom(&self) -> Result<()>;
+        fn get_save_status(&self) -> Result<Option<SaveParameterStatus>>;
+        fn is_save_in_progress(&self) -> Result<bool>;
+        fn param_reset(&self) -> Result<()>;
+        fn factory_reset(&self) -> Result<()>;
+        fn save_mapping_eeprom(&self) -> Result<()>;
+        fn save_mapping_eeprom_verified(&self, timeout: Duration) -> Result<()>;
+        fn reset_current_alarm(&self) -> Result<()>;
+        fn reset_history_alarm(&self) -> Result<()>;
+        fn jog_motor(&self, direction: Direction) -> Result<()>;
+        fn start_repetitive_jog(&self, config: &RepetitiveJogConfig) -> Result<()>;
+        fn stop_repetitive_jog(&self) -> Result<()>;
+        fn configure_input(&self, input_no: u8, function: DigitalInputFunction, normally_closed: bool) -> Result<()>;
+        fn configure_output(&self, output_no: u8, function: DigitalOutputFunction, normally_closed: bool) -> Result<()>;
+        fn configure_jog_input(&self, input_no: u8, velocity_rpm: u16) -> Result<()>;
+        fn get_input_status(&self) -> Result<DigitalInputStatus>;
+        fn get_output_status(&self) -> Result<DigitalOutputStatus>;
+        fn is_brake_engaged(&self) -> Result<bool>;
+        fn read_status_block(&self) -> Result<StatusBlock>;
+        fn get_bus_voltage(&self) -> Result<f32>;
+        fn get_motion_status(&self) -> Result<MotionStatus>;
+        fn get_current_position(&self) -> Result<i32>;
+        fn is_path_completed(&self) -> Result<bool>;
+        fn is_homing_completed(&self) -> Result<bool>;
+        fn wait_for_path_complete(&self, poll_interval: Duration, timeout: Duration) -> Result<()>;
+        fn wait_for_homing_complete(&self, poll_interval: Duration, timeout: Duration) -> Result<()>;
+        fn wait_until(&self, conditions: &[StopCondition], poll_interval: Duration, timeout: Duration) -> Result<StopCondition>;
+        fn set_ctrg_effective_edge(&self, double_edge: bool) -> Result<()>;
+        fn get_ctrg_config(&self) -> Result<CtrgConfig>;
+        fn set_ctrg_config(&self, ctrg: CtrgConfig) -> Result<()>;
+        fn soft_limit_control(&self, enable: bool) -> Result<()>;
+        fn set_soft_limit_max(&self, max: u32) -> Result<()>;
+        fn set_soft_limit_min(&self, min: u32) -> Result<()>;
+        fn set_soft_limit_max_signed(&self, max: i32) -> Result<()>;
+        fn set_soft_limit_min_signed(&self, min: i32) -> Result<()>;
+        fn apply_soft_limits(&self, limits: SoftLimits) -> Result<()>;
+        fn get_soft_limits(&self) -> Result<SoftLimits>;
+        fn move_to_clamped(&self, path_id: u8, target: i32, velocity: u16, clamp: bool) -> Result<bool>;
+        fn move_relative(&self, path_id: u8, delta: i32, velocity: u16) -> Result<()>;
+        fn apply_brake_config(&self, brake: BrakeConfig) -> Result<()>;
+        fn set_brake_threshold_rpm(&self, rpm: u16) -> Result<()>;
+        fn get_brake_threshold_rpm(&self) -> Result<u16>;
+        fn homing_power_up_control(&self, enable: bool) -> Result<()>;
+        fn set_ctrg_trigger_type(&self, level_trigger: bool) -> Result<()>;
+        fn get_global_control_flags(&self) -> Result<GlobalControlFlags>;
+        fn set_global_control_flags(&self, ctrl_flags: GlobalControlFlags) -> Result<()>;
+        fn configure_homing(&self, direction: Direction, move_to_pos: bool, method: HomingMethod) -> Result<()>;
+        fn set_homing_position(&self, position: u32) -> Result<()>;
+        fn set_homing_position_signed(&self, position: i32) -> Result<()>;
+        fn set_homing_stop_position(&self, position: u32) -> Result<()>;
+        fn set_homing_stop_position_signed(&self, position: i32) -> Result<()>;
+        fn set_homing_high_velocity(&self, rpm: u16) -> Result<()>;
+        fn set_homing_low_velocity(&self, rpm: u16) -> Result<()>;
+        fn set_homing_acceleration(&self, acc: u16) -> Result<()>;
+        fn set_homing_acceleration_ms(&self, ms: u32) -> Result<()>;
+        fn set_homing_deceleration(&self, dec: u16) -> Result<()>;
+        fn get_homing_config(&self) -> Result<HomingConfig>;
+        fn apply_homing_config(&self, config: &HomingConfig) -> Result<()>;
+        fn configure_referencing(&self, homing: &HomingConfig, min: i32, max: i32) -> Result<()>;
+        fn start_homing(&self) -> Result<()>;
+        fn start_path(&self, path_id: u8) -> Result<()>;
+        fn interrupt_with_path(&self, path_id: u8) -> Result<()>;
+        fn stop_motor(&self) -> Result<()>;
+        fn emergency_stop(&self) -> Result<()>;
+        fn stop_and_wait(&self, timeout: Duration) -> Result<Duration>;
+        fn manual_zero(&self) -> Result<()>;
+        fn reset_to_safe_state(&self) -> Result<()>;
+        fn set_path_position(&self, path_id: u8, position: u32) -> Result<()>;
+        fn set_path_position_signed(&self, path_id: u8, position: i32) -> Result<()>;
+        fn set_path_velocity(&self, path_id: u8, rpm: u16) -> Result<()>;
+        fn set_path_acceleration(&self, path_id: u8, acc: u16) -> Result<()>;
+        fn set_path_deceleration(&self, path_id: u8, dec: u16) -> Result<()>;
+        fn set_path_pause_time(&self, path_id: u8, ms: u16) -> Result<()>;
+        fn get_path_control(&self, path_id: u8) -> Result<u16>;
+        fn get_path_position(&self, path_id: u8) -> Result<u32>;
+        fn get_path_target(&self, path_id: u8) -> Result<i32>;
+        fn get_path_velocity(&self, path_id: u8) -> Result<u16>;
+        fn get_path_acceleration(&self, path_id: u8) -> Result<u16>;
+        fn get_path_deceleration(&self, path_id: u8) -> Result<u16>;
+        fn get_path_pause_time(&self, path_id: u8) -> Result<u16>;
+        fn read_path_config(&self, path_id: u8) -> Result<Option<PathConfig>>;
+        fn read_all_paths(&self) -> Result<Vec<PathConfig>>;
+        fn apply_path_config(&self, config: &PathConfig) -> Result<()>;
+        fn config_fingerprint(&self) -> Result<u64>;
+        fn get_version(&self) -> Result<u16>;
+        fn require_firmware(&self, min: FirmwareVersion) -> Result<()>;
+        fn get_current_alarm(&self) -> Result<CurrentAlarm>;
+        fn error_if_alarmed(&self) -> Result<()>;
+    }
+
+    /// Locking counterpart of [`Em2rsSyncClient::set_peak_current_scale`]
+    pub fn set_peak_current_scale(&self, scale: f32) {
+        self.lock().set_peak_current_scale(scale)
+    }
+
+    /// Locking counterpart of [`Em2rsSyncClient::with_reduced_current`]
+    ///
+    /// The client stays locked while `f` runs.
+    pub fn with_reduced_current<T>(
+        &self,
+        phase_current: f32,
+        f: impl FnOnce(&mut Em2rsSyncClient) -> Result<T>,
+    ) -> Result<T> {
+        self.lock().with_reduced_current(phase_current, f)
+    }
+
+    /// Locking counterpart of [`Em2rsSyncClient::with_global_control`]
+    ///
+    /// The client stays locked while `body` runs.
+    pub fn with_global_control<T>(
+        &self,
+        modify: impl FnOnce(&mut GlobalControlFlags),
+        body: impl FnOnce(&mut Em2rsSyncClient) -> Result<T>,
+    ) -> Result<T> {
+        self.lock().with_global_control(modify, body)
+    }
+
+    /// Locking counterpart of [`Em2rsSyncClient::validate_against_motor`]
+    pub fn validate_against_motor(
+        &self,
+        supported_range: impl Fn(u16) -> Option<RangeInclusive<u16>>,
+    ) -> Result<()> {
+        self.lock().validate_against_motor(supported_range)
+    }
+
+    /// Locking counterpart of [`Em2rsSyncClient::configure_path_motion`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure_path_motion(
+        &self,
+        path_id: u8,
+        motion_type: PathMotionType,
+        interrupt: bool,
+        overlap: bool,
+        absolute: bool,
+        jump: bool,
+        jump_to: u8,
+    ) -> Result<()> {
+        self.lock().configure_path_motion(path_id, motion_type, interrupt, overlap, absolute, jump, jump_to)
+    }
+}
+
+impl From<Em2rsSyncClient> for Em2rsSharedClient {
+    fn from(client: Em2rsSyncClient) -> Self {
+        Self::new(client)
+    }
+}
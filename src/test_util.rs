@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use async_trait::async_trait;
+use tokio_modbus::prelude::*;
+
+/// In-memory EM2RS register bank for hardware-free testing
+///
+/// `MemoryDrive` implements the tokio-modbus [`Client`] trait on top of a
+/// `HashMap<u16, u16>`: holding register writes are stored and reads return
+/// what was written, or 0 for registers never written. Clones share the same
+/// register bank, so a handle can be kept to inspect the registers after the
+/// context has been moved into an [`Em2rsClient`](crate::Em2rsClient).
+///
+/// Only the async client can be backed by a `MemoryDrive`, as tokio-modbus
+/// offers no way to build a sync context from a custom client.
+///
+/// # Example
+/// ```
+/// use em2rs::test_util::MemoryDrive;
+/// use em2rs::{registers, Em2rsClient, PathConfig, StepperConfig};
+///
+/// # tokio_test::block_on(async {
+/// let drive = MemoryDrive::new();
+/// let mut client = Em2rsClient::new(drive.context(), StepperConfig::new(1, 10000));
+/// client.init().await.unwrap();
+///
+/// let path = PathConfig::new(1).unwrap().with_velocity(300);
+/// client.apply_path_config(&path).await.unwrap();
+///
+/// assert_eq!(drive.get(registers::PULSE_PER_REV), Some(10000));
+/// assert_eq!(drive.get(registers::PATH1_BASE + registers::PATH_VELOCITY_OFFSET), Some(300));
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryDrive {
+    state: Arc<Mutex<State>>,
+    slave: Option<u8>,
+}
+
+/// Registers and request log shared by the clones of a `MemoryDrive`
+#[derive(Debug, Default)]
+struct State {
+    registers: HashMap<u16, u16>,
+    writes: Vec<(u16, Vec<u16>)>,
+    read_only: HashSet<u16>,
+    exceptions: HashMap<u16, ExceptionCode>,
+    slaves: Option<HashSet<u8>>,
+    scripts: HashMap<u16, VecDeque<u16>>,
+}
+
+impl State {
+    /// Exception configured for any register in `count` registers from `addr`
+    fn exception(&self, addr: u16, count: u16) -> Option<ExceptionCode> {
+        (0..count).find_map(|i| self.exceptions.get(&addr.wrapping_add(i)).copied())
+    }
+
+    /// Value read from a register, advancing its script if it has one
+    fn read(&mut self, addr: u16) -> u16 {
+        if let Some(value) = self.scripts.get_mut(&addr).and_then(VecDeque::pop_front) {
+            self.registers.insert(addr, value);
+        }
+        self.registers.get(&addr).copied().unwrap_or(0)
+    }
+
+    /// Store a written value unless the register ignores writes
+    fn store(&mut self, addr: u16, value: u16) {
+        if !self.read_only.contains(&addr) {
+            self.registers.insert(addr, value);
+        }
+    }
+}
+
+impl MemoryDrive {
+    /// Create a drive with an empty register bank
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tokio-modbus context backed by this drive
+    pub fn context(&self) -> client::Context {
+        let client: Box<dyn Client> = Box::new(self.clone());
+        client::Context::from(client)
+    }
+
+    /// Value of a register, or `None` if it was never written
+    pub fn get(&self, addr: u16) -> Option<u16> {
+        self.lock().registers.get(&addr).copied()
+    }
+
+    /// Preset a register, e.g. to simulate a status read by the client
+    pub fn set(&self, addr: u16, value: u16) {
+        self.lock().registers.insert(addr, value);
+    }
+
+    /// Snapshot of the current contents of every register set or written
+    pub fn registers(&self) -> HashMap<u16, u16> {
+        self.lock().registers.clone()
+    }
+
+    /// Every write request received, in order, as start address and values
+    ///
+    /// A single register write is logged with one value, a multiple register
+    /// write with all of them. Registers preset with [`set`](Self::set) are
+    /// not logged.
+    pub fn writes(&self) -> Vec<(u16, Vec<u16>)> {
+        self.lock().writes.clone()
+    }
+
+    /// Accept writes to a register without changing its value
+    ///
+    /// Simulates a register the drive clamps or does not implement, so the
+    /// value read back differs from the value written.
+    pub fn ignore_writes(&self, addr: u16) {
+        self.lock().read_only.insert(addr);
+    }
+
+    /// Answer every request touching a register with a Modbus exception
+    ///
+    /// Reads and writes covering `addr` fail with `code` and leave the
+    /// registers unchanged; writes are still logged.
+    pub fn fail_at(&self, addr: u16, code: ExceptionCode) {
+        self.lock().exceptions.insert(addr, code);
+    }
+
+    /// Only answer requests addressed to the given slave IDs
+    ///
+    /// Requests to any other ID never get a reply, like on a bus where no
+    /// drive has that address, so the caller's timeout expires. By default
+    /// every ID is answered.
+    pub fn answer_only(&self, ids: impl IntoIterator<Item = u8>) {
+        self.lock().slaves = Some(ids.into_iter().collect());
+    }
+
+    /// Make successive reads of a register return `values` in turn
+    ///
+    /// Once the values are used up, the register keeps the last one. Useful
+    /// to play back a status register changing while the client polls it.
+    pub fn script(&self, addr: u16, values: impl IntoIterator<Item = u16>) {
+        self.lock().scripts.insert(addr, values.into_iter().collect());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SlaveContext for MemoryDrive {
+    fn set_slave(&mut self, slave: Slave) {
+        self.slave = Some(slave.into());
+    }
+}
+
+#[async_trait]
+impl Client for MemoryDrive {
+    async fn call(&mut self, request: Request<'_>) -> tokio_modbus::Result<Response> {
+        let absent = match (&self.lock().slaves, self.slave) {
+            (Some(slaves), Some(slave)) => !slaves.contains(&slave),
+            _ => false,
+        };
+        if absent {
+            return std::future::pending().await;
+        }
+        let mut state = self.lock();
+        if let Request::WriteSingleRegister(addr, value) = request {
+            state.writes.push((addr, vec![value]));
+        } else if let Request::WriteMultipleRegisters(addr, ref values) = request {
+            state.writes.push((addr, values.to_vec()));
+        }
+        let exception = match request {
+            Request::ReadHoldingRegisters(addr, count) => state.exception(addr, count),
+            Request::WriteSingleRegister(addr, _) => state.exception(addr, 1),
+            Request::WriteMultipleRegisters(addr, ref values) => {
+                state.exception(addr, values.len() as u16)
+            }
+            _ => None,
+        };
+        if let Some(code) = exception {
+            return Ok(Err(code));
+        }
+        let response = match request {
+            Request::ReadHoldingRegisters(addr, count) => Response::ReadHoldingRegisters(
+                (0..count).map(|i| state.read(addr.wrapping_add(i))).collect(),
+            ),
+            Request::WriteSingleRegister(addr, value) => {
+                state.store(addr, value);
+                Response::WriteSingleRegister(addr, value)
+            }
+            Request::WriteMultipleRegisters(addr, values) => {
+                for (i, value) in values.iter().enumerate() {
+                    state.store(addr.wrapping_add(i as u16), *value);
+                }
+                Response::WriteMultipleRegisters(addr, values.len() as u16)
+            }
+            _ => return Ok(Err(ExceptionCode::IllegalFunction)),
+        };
+        Ok(Ok(response))
+    }
+
+    async fn disconnect(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 use tokio_modbus::ExceptionCode;
 
@@ -22,28 +23,154 @@ pub enum Em2rsError {
     #[error("Invalid digital input: {0}. Must be 1-7")]
     InvalidDigitalInput(u8),
     
+    #[error("Invalid digital output: {0}. Must be 1-3")]
+    InvalidDigitalOutput(u8),
+    
     #[error("Operation failed: {0}")]
     OperationFailed(String),
+
+    #[error("Motor fault: {0}")]
+    MotorFault(CurrentAlarm),
+
+    #[error("Homing failed: {0}")]
+    HomingFailed(String),
+
+    #[error("Unsupported firmware {found}, {required} or newer required")]
+    UnsupportedFirmware {
+        found: FirmwareVersion,
+        required: FirmwareVersion,
+    },
+
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("Short response: expected {expected} registers, got {got}")]
+    ShortResponse { expected: usize, got: usize },
+
+    #[error("Register {addr:#06x} reads back {actual:#06x}, expected {expected:#06x}")]
+    VerificationFailed { addr: u16, expected: u16, actual: u16 },
+
+    #[error("{section} configuration failed: {source}")]
+    Section {
+        section: ConfigSection,
+        source: Box<Em2rsError>,
+    },
+
+    #[error("{source} at register {addr:#06x}")]
+    At {
+        addr: u16,
+        source: Box<Em2rsError>,
+    },
+}
+
+impl Em2rsError {
+    /// Attach the address of the register being accessed
+    pub(crate) fn at(self, addr: u16) -> Self {
+        Self::At { addr, source: Box::new(self) }
+    }
+
+    /// Attach the machine configuration section being applied
+    pub(crate) fn in_section(self, section: ConfigSection) -> Self {
+        Self::Section { section, source: Box::new(self) }
+    }
+
+    /// Address of the register whose access failed, if known
+    pub fn register(&self) -> Option<u16> {
+        match self {
+            Self::At { addr, .. } | Self::VerificationFailed { addr, .. } => Some(*addr),
+            Self::Section { source, .. } => source.register(),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without register or section context
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::At { source, .. } | Self::Section { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Em2rsError>;
 
+/// Kind of Modbus transaction reported to a [`TransactionTimer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionOp {
+    Read,
+    Write,
+}
+
+/// Receives the round-trip time of every Modbus transaction
+///
+/// Register one with `with_transaction_timer` to build latency statistics,
+/// e.g. a histogram per operation. `record` is called after each request,
+/// including failed ones, with the time spent waiting for the drive; the
+/// inter-frame delay of the `modbus-delay` feature is not included.
+pub trait TransactionTimer: Send + Sync {
+    /// Record one transaction starting at register `addr`
+    fn record(&self, op: TransactionOp, addr: u16, elapsed: Duration);
+}
+
 /// Motor rotation direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum Direction {
     Clockwise = 0x00,
     CounterClockwise = 0x01,
 }
 
+impl Direction {
+    /// Encode as the direction bit shared by `MOTOR_DIRECTION` and bit 0 of
+    /// `HOME_MODE`
+    ///
+    /// Every direction written by the clients goes through this encoding, jog
+    /// included (see [`ControlWord::jog`]), so a given `Direction` means the
+    /// same rotation for homing, jogging and paths. All of them are relative to
+    /// the `MOTOR_DIRECTION` setting: reversing it reverses them together.
+    ///
+    /// ```
+    /// use em2rs::Direction;
+    ///
+    /// for dir in [Direction::Clockwise, Direction::CounterClockwise] {
+    ///     assert_eq!(Direction::from_bit(dir.to_bit()), dir);
+    ///     assert_eq!(dir.reversed().reversed(), dir);
+    /// }
+    /// assert_eq!(Direction::Clockwise.to_bit(), 0);
+    /// assert_eq!(Direction::from_bit(0x0007), Direction::CounterClockwise);
+    /// ```
+    pub fn to_bit(self) -> u16 {
+        self as u16
+    }
+
+    /// Decode from bit 0 of a register value, ignoring the other bits
+    pub fn from_bit(value: u16) -> Self {
+        if value & 0x0001 == 0 {
+            Self::Clockwise
+        } else {
+            Self::CounterClockwise
+        }
+    }
+
+    /// The opposite direction
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Clockwise => Self::CounterClockwise,
+            Self::CounterClockwise => Self::Clockwise,
+        }
+    }
+}
+
 impl From<Direction> for u16 {
     fn from(dir: Direction) -> Self {
-        dir as u16
+        dir.to_bit()
     }
 }
 
 /// Digital input configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum DigitalInputFunction {
     Invalid = 0x00,
@@ -70,6 +197,91 @@ impl From<DigitalInputFunction> for u16 {
     }
 }
 
+/// Digital input status, as read from `DIGITAL_INPUT_STATUS`
+///
+/// Bit `n - 1` stands for input `n`.
+///
+/// ```
+/// use em2rs::DigitalInputStatus;
+///
+/// let status = DigitalInputStatus(0b100_0001);
+/// assert!(status.is_input_active(1));
+/// assert!(!status.is_input_active(2));
+/// assert!(status.is_input_active(7));
+/// assert!(!status.is_input_active(8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DigitalInputStatus(pub u16);
+
+impl DigitalInputStatus {
+    /// Check if input `input_no` (1-7) is active
+    pub fn is_input_active(&self, input_no: u8) -> bool {
+        (1..=7).contains(&input_no) && self.0 & (1 << (input_no - 1)) != 0
+    }
+}
+
+/// Rising and falling edges between two digital input reads
+///
+/// Bit `n - 1` stands for input `n`, as in `DIGITAL_INPUT_STATUS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InputEdges {
+    pub rising: u16,
+    pub falling: u16,
+}
+
+impl InputEdges {
+    /// Check if input `input_no` (1-7) went from inactive to active
+    pub fn is_rising(&self, input_no: u8) -> bool {
+        (1..=7).contains(&input_no) && self.rising & (1 << (input_no - 1)) != 0
+    }
+
+    /// Check if input `input_no` (1-7) went from active to inactive
+    pub fn is_falling(&self, input_no: u8) -> bool {
+        (1..=7).contains(&input_no) && self.falling & (1 << (input_no - 1)) != 0
+    }
+}
+
+/// Host-side edge detection over successive `get_input_status` reads
+///
+/// The EM2RS has no latched input register: a pulse shorter than the polling
+/// interval can still be missed, so poll at least twice per expected pulse.
+///
+/// ```
+/// use em2rs::{DigitalInputStatus, InputEdgeDetector};
+///
+/// let mut detector = InputEdgeDetector::new();
+/// assert_eq!(detector.update(DigitalInputStatus(0b001)).rising, 0);
+/// let edges = detector.update(DigitalInputStatus(0b010));
+/// assert!(edges.is_rising(2));
+/// assert!(edges.is_falling(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputEdgeDetector {
+    last: Option<DigitalInputStatus>,
+}
+
+impl InputEdgeDetector {
+    /// Create a detector with no reference status yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a new input status and get the edges since the previous one
+    ///
+    /// The first status only sets the reference and reports no edges.
+    pub fn update(&mut self, status: DigitalInputStatus) -> InputEdges {
+        let edges = match self.last {
+            Some(last) => InputEdges {
+                rising: status.0 & !last.0,
+                falling: last.0 & !status.0,
+            },
+            None => InputEdges::default(),
+        };
+        self.last = Some(status);
+        edges
+    }
+}
+
 /// Digital output configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u16)]
@@ -78,6 +290,9 @@ pub enum DigitalOutputFunction {
     CmdCompleted = 0x20,
     PathCompleted = 0x21,
     HomingCompleted = 0x22,
+    /// Motion command finished. The EM2RS runs open loop, so there is no
+    /// in-position window or settling time register to tune: the output
+    /// follows the end of the command rather than a measured position error.
     InPosCompleted = 0x23,
     BrakeOutput = 0x24,
     AlarmOutput = 0x25,
@@ -89,7 +304,79 @@ impl From<DigitalOutputFunction> for u16 {
     }
 }
 
+/// Digital output status, as read from `DIGITAL_OUTPUT_STATUS`
+///
+/// Bit `n - 1` stands for output `n`.
+///
+/// ```
+/// use em2rs::DigitalOutputStatus;
+///
+/// let status = DigitalOutputStatus(0b110);
+/// assert!(!status.is_output_active(1));
+/// assert!(status.is_output_active(2));
+/// assert!(status.is_output_active(3));
+/// assert!(!status.is_output_active(0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DigitalOutputStatus(pub u16);
+
+impl DigitalOutputStatus {
+    /// Check if output `output_no` (1-3) is active
+    pub fn is_output_active(&self, output_no: u8) -> bool {
+        (1..=3).contains(&output_no) && self.0 & (1 << (output_no - 1)) != 0
+    }
+}
+
+/// RS485 baud rate, as stored in `RS485_BAUDRATE`
+///
+/// The register holds an index rather than the rate itself.
+///
+/// ```
+/// use em2rs::Baudrate;
+///
+/// assert_eq!(u16::from(Baudrate::B2400), 0);
+/// assert_eq!(u16::from(Baudrate::B9600), 2);
+/// assert_eq!(u16::from(Baudrate::B115200), 6);
+/// assert_eq!(Baudrate::B38400.bits_per_second(), 38400);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum Baudrate {
+    B2400 = 0,
+    B4800 = 1,
+    B9600 = 2,
+    B19200 = 3,
+    B38400 = 4,
+    B57600 = 5,
+    B115200 = 6,
+}
+
+impl Baudrate {
+    /// Baud rate in bits per second, e.g. for `tokio_serial::new`
+    pub fn bits_per_second(&self) -> u32 {
+        match self {
+            Baudrate::B2400 => 2400,
+            Baudrate::B4800 => 4800,
+            Baudrate::B9600 => 9600,
+            Baudrate::B19200 => 19200,
+            Baudrate::B38400 => 38400,
+            Baudrate::B57600 => 57600,
+            Baudrate::B115200 => 115200,
+        }
+    }
+}
+
+impl From<Baudrate> for u16 {
+    fn from(baud: Baudrate) -> Self {
+        baud as u16
+    }
+}
+
 /// Control word commands
+///
+/// The EM2RS has no software reset or reboot command. Parameters that only
+/// apply after a restart, such as the RS485 baud rate or the control mode, must
+/// be saved with `SaveParamEeprom` and take effect after a power cycle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u16)]
 pub enum ControlWord {
@@ -103,6 +390,23 @@ pub enum ControlWord {
     JogCounterClockwise = 0x4002,
 }
 
+impl ControlWord {
+    /// Jog command for a direction
+    ///
+    /// ```
+    /// use em2rs::{ControlWord, Direction};
+    ///
+    /// assert_eq!(ControlWord::jog(Direction::Clockwise), ControlWord::JogClockwise);
+    /// assert_eq!(ControlWord::jog(Direction::CounterClockwise), ControlWord::JogCounterClockwise);
+    /// ```
+    pub fn jog(direction: Direction) -> Self {
+        match direction {
+            Direction::Clockwise => Self::JogClockwise,
+            Direction::CounterClockwise => Self::JogCounterClockwise,
+        }
+    }
+}
+
 impl From<ControlWord> for u16 {
     fn from(cw: ControlWord) -> Self {
         cw as u16
@@ -117,6 +421,30 @@ pub enum SaveParameterStatus {
     FailedToSave = 0xAAAA,
 }
 
+impl SaveParameterStatus {
+    /// Decode the status word, `None` while a save is still in progress
+    pub fn from_word(word: u16) -> Option<Self> {
+        match word {
+            0x5555 => Some(Self::SaveSuccessfully),
+            0xAAAA => Some(Self::FailedToSave),
+            _ => None,
+        }
+    }
+}
+
+/// Firmware version as reported by `VERSION_INFORMATION`
+///
+/// The drive documents no major/minor encoding, so versions are compared as
+/// raw register values, newer firmware reporting higher values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion(pub u16);
+
+impl std::fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#06x}", self.0)
+    }
+}
+
 /// Current alarm flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CurrentAlarm(pub u16);
@@ -129,6 +457,13 @@ impl CurrentAlarm {
     pub const EEPROM_FAULT: u16 = 0x200;
     pub const AUTOTUNING_FAULT: u16 = 0x100;
 
+    /// Over-current fault
+    ///
+    /// The EM2RS exposes no register to tune the over-current or
+    /// current-sampling fault thresholds: they are fixed by the drive hardware.
+    /// Nuisance over-current trips on startup transients are best addressed by
+    /// lowering the peak current (`set_peak_current`) or the ramps rather than
+    /// by a host-side threshold.
     pub fn has_over_current(&self) -> bool {
         self.0 & Self::OVER_CURRENT != 0
     }
@@ -152,10 +487,63 @@ impl CurrentAlarm {
     pub fn has_autotuning_fault(&self) -> bool {
         self.0 & Self::AUTOTUNING_FAULT != 0
     }
+
+    /// Names of the active alarms, in bit order
+    ///
+    /// Bits without a known alarm are left out; `Display` reports them.
+    ///
+    /// ```
+    /// use em2rs::CurrentAlarm;
+    ///
+    /// assert!(CurrentAlarm(0).active_faults().is_empty());
+    /// assert_eq!(CurrentAlarm(CurrentAlarm::OVER_VOLTAGE).active_faults(), ["OverVoltage"]);
+    ///
+    /// let alarm = CurrentAlarm(CurrentAlarm::OVER_CURRENT | CurrentAlarm::EEPROM_FAULT);
+    /// assert_eq!(alarm.active_faults(), ["OverCurrent", "EepromFault"]);
+    /// assert_eq!(alarm.to_string(), "OverCurrent, EepromFault");
+    /// assert_eq!(CurrentAlarm(0).to_string(), "No alarm");
+    /// assert_eq!(CurrentAlarm(0x0401).to_string(), "OverCurrent, Unknown(0x0400)");
+    /// ```
+    pub fn active_faults(&self) -> Vec<&'static str> {
+        ALARM_NAMES
+            .iter()
+            .filter(|&&(bit, _)| self.0 & bit != 0)
+            .map(|&(_, name)| name)
+            .collect()
+    }
+}
+
+/// Display name of each `CurrentAlarm` bit, in bit order
+const ALARM_NAMES: [(u16, &str); 6] = [
+    (CurrentAlarm::OVER_CURRENT, "OverCurrent"),
+    (CurrentAlarm::OVER_VOLTAGE, "OverVoltage"),
+    (CurrentAlarm::CURRENT_SAMPLING_FAULT, "CurrentSamplingFault"),
+    (CurrentAlarm::FAILED_LOCK_SHAFT, "FailedLockShaft"),
+    (CurrentAlarm::AUTOTUNING_FAULT, "AutotuningFault"),
+    (CurrentAlarm::EEPROM_FAULT, "EepromFault"),
+];
+
+impl std::fmt::Display for CurrentAlarm {
+    /// Lists the active alarms, e.g. `OverCurrent, OverVoltage`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "No alarm");
+        }
+        let mut names = self.active_faults().join(", ");
+        let unknown = self.0 & !ALARM_NAMES.iter().fold(0, |known, &(bit, _)| known | bit);
+        if unknown != 0 {
+            if !names.is_empty() {
+                names.push_str(", ");
+            }
+            names.push_str(&format!("Unknown({unknown:#06x})"));
+        }
+        write!(f, "{names}")
+    }
 }
 
 /// Homing method
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum HomingMethod {
     LimitSwitch = 0x00,
@@ -168,6 +556,16 @@ impl From<HomingMethod> for u16 {
     }
 }
 
+/// Decode `HOME_MODE` into direction, move to position after homing and method
+pub(crate) fn decode_home_mode(reg: u16) -> (Direction, bool, HomingMethod) {
+    let method = if reg & u16::from(HomingMethod::HomeSwitch) != 0 {
+        HomingMethod::HomeSwitch
+    } else {
+        HomingMethod::LimitSwitch
+    };
+    (Direction::from_bit(reg), reg & 0x0002 != 0, method)
+}
+
 /// PR control register commands
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u16)]
@@ -186,6 +584,7 @@ impl From<PrControlCommand> for u16 {
 
 /// Path motion type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum PathMotionType {
     NoAction = 0x00,
@@ -200,11 +599,59 @@ impl From<PathMotionType> for u16 {
     }
 }
 
+impl PathMotionType {
+    /// Decode from the low 4 bits of a path control word, `None` if unknown
+    pub fn from_bits(ctrl: u16) -> Option<Self> {
+        match ctrl & 0x000F {
+            0x00 => Some(Self::NoAction),
+            0x01 => Some(Self::PositionPositioning),
+            0x02 => Some(Self::VelocityMovement),
+            0x03 => Some(Self::Homing),
+            _ => None,
+        }
+    }
+}
+
+/// Single display state decoded from [`MotionStatus`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotorState {
+    Disabled,
+    Idle,
+    Moving,
+    Homing,
+    Faulted,
+}
+
 /// Motion status flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MotionStatus(pub u16);
 
 impl MotionStatus {
+    /// Collapse the flags into a single state
+    ///
+    /// Several flags can be set at once, so the first matching rule wins:
+    /// 1. `Faulted` if the fault flag is set
+    /// 2. `Homing` if running while the homing complete flag is clear
+    /// 3. `Moving` if running
+    /// 4. `Idle` if enabled
+    /// 5. `Disabled` otherwise
+    ///
+    /// The drive has no homing-in-progress flag: any move made before homing
+    /// has completed once since power up is reported as `Homing`.
+    pub fn state(&self) -> MotorState {
+        if self.is_fault() {
+            MotorState::Faulted
+        } else if self.is_running() && !self.is_homing_complete() {
+            MotorState::Homing
+        } else if self.is_running() {
+            MotorState::Moving
+        } else if self.is_enabled() {
+            MotorState::Idle
+        } else {
+            MotorState::Disabled
+        }
+    }
+
     pub fn is_fault(&self) -> bool {
         self.0 & crate::registers::flags::MS_FAULT != 0
     }
@@ -228,10 +675,173 @@ impl MotionStatus {
     pub fn is_homing_complete(&self) -> bool {
         self.0 & crate::registers::flags::MS_HOMING_COMPLETE != 0
     }
+
+    /// Check if the last motion stopped without reaching its target
+    ///
+    /// The drive has no dedicated interrupted flag. The command complete flag
+    /// is set whenever a PR command finishes, including a quick stop, while the
+    /// path complete flag is only set when a path reaches its target. A motor
+    /// at standstill with the former set and the latter clear was therefore
+    /// stopped before completing its path.
+    pub fn was_interrupted(&self) -> bool {
+        !self.is_running() && self.is_cmd_complete() && !self.is_path_complete()
+    }
+
+    /// Check if the motor is enabled and holding position at standstill
+    ///
+    /// True when enabled, not running, with the last command complete and no
+    /// fault. The EM2RS runs open loop and reports no separate holding or
+    /// servo-ready flag: holding means the windings are energized at
+    /// standstill, at the standby current once the standby switching time has
+    /// elapsed, not that the position was verified.
+    pub fn is_holding(&self) -> bool {
+        self.is_enabled() && !self.is_running() && self.is_cmd_complete() && !self.is_fault()
+    }
+}
+
+/// Decoded `PR_GLOBAL_CTRL_FCT` register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GlobalControlFlags {
+    /// CTRG effective on both edges instead of a single edge
+    pub ctrg_double_edge: bool,
+    /// Soft limits enabled
+    pub soft_limit_enabled: bool,
+    /// Homing runs on power up
+    pub homing_on_power_up: bool,
+    /// CTRG is level triggered instead of edge triggered
+    pub ctrg_level_trigger: bool,
+}
+
+impl GlobalControlFlags {
+    /// Bits of `PR_GLOBAL_CTRL_FCT` covered by these flags
+    pub(crate) const MASK: u16 = crate::registers::flags::PR_CTRG_DOUBLE_EDGE
+        | crate::registers::flags::PR_SOFT_LIMIT_ENABLE
+        | crate::registers::flags::PR_HOMING_POWER_UP
+        | crate::registers::flags::PR_CTRG_LEVEL_TRIGGER;
+}
+
+impl From<u16> for GlobalControlFlags {
+    fn from(reg: u16) -> Self {
+        use crate::registers::flags;
+        Self {
+            ctrg_double_edge: reg & flags::PR_CTRG_DOUBLE_EDGE != 0,
+            soft_limit_enabled: reg & flags::PR_SOFT_LIMIT_ENABLE != 0,
+            homing_on_power_up: reg & flags::PR_HOMING_POWER_UP != 0,
+            ctrg_level_trigger: reg & flags::PR_CTRG_LEVEL_TRIGGER != 0,
+        }
+    }
+}
+
+impl From<GlobalControlFlags> for u16 {
+    fn from(f: GlobalControlFlags) -> Self {
+        use crate::registers::flags;
+        let mut reg = 0;
+        if f.ctrg_double_edge {
+            reg |= flags::PR_CTRG_DOUBLE_EDGE;
+        }
+        if f.soft_limit_enabled {
+            reg |= flags::PR_SOFT_LIMIT_ENABLE;
+        }
+        if f.homing_on_power_up {
+            reg |= flags::PR_HOMING_POWER_UP;
+        }
+        if f.ctrg_level_trigger {
+            reg |= flags::PR_CTRG_LEVEL_TRIGGER;
+        }
+        reg
+    }
+}
+
+/// Status registers read together by `read_status_block`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusBlock {
+    /// Bus voltage in volts
+    pub bus_voltage: f32,
+    /// Digital input status
+    pub inputs: DigitalInputStatus,
+    /// Digital output status
+    pub outputs: DigitalOutputStatus,
+}
+
+/// Convert a `BUS_VOLTAGE` value, in 0.1 V, to volts
+pub(crate) fn bus_voltage_from_raw(raw: u16) -> f32 {
+    raw as f32 / 10.0
+}
+
+/// Condition waited for by `wait_until`
+///
+/// The drive reports no actual velocity, so there is no velocity condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopCondition {
+    PathComplete,
+    HomingComplete,
+    /// Digital input 1-7 is active
+    InputActive(u8),
+    Fault,
+}
+
+/// CTRG trigger configuration, decoded from `PR_GLOBAL_CTRL_FCT`
+///
+/// Only the valid combinations of the edge and trigger type bits are
+/// modelled: the edge setting only matters for edge triggering, so `Level`
+/// is always written with the double edge bit cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtrgConfig {
+    /// Triggered on a single edge
+    SingleEdge,
+    /// Triggered on both edges
+    DoubleEdge,
+    /// Triggered by level
+    Level,
+}
+
+impl From<GlobalControlFlags> for CtrgConfig {
+    fn from(flags: GlobalControlFlags) -> Self {
+        if flags.ctrg_level_trigger {
+            Self::Level
+        } else if flags.ctrg_double_edge {
+            Self::DoubleEdge
+        } else {
+            Self::SingleEdge
+        }
+    }
 }
 
 /// Homing configuration
-#[derive(Debug, Clone)]
+///
+/// With the `serde` feature, this and the other configuration types can be
+/// stored, e.g. as JSON:
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use em2rs::{DigitalInputFunction, Direction, HomingConfig, HomingMethod, StepperConfig};
+///
+/// let homing = HomingConfig {
+///     input_no: 3,
+///     function: DigitalInputFunction::Org,
+///     normally_closed: true,
+///     direction: Direction::CounterClockwise,
+///     move_to_pos_after: false,
+///     method: HomingMethod::LimitSwitch,
+///     position: 1200,
+///     position_stop: 0xFFFF_FC18,
+///     high_velocity: 250,
+///     low_velocity: 20,
+///     acceleration: 80,
+///     deceleration: 120,
+/// };
+/// let json = serde_json::to_string(&homing).unwrap();
+/// assert_eq!(serde_json::from_str::<HomingConfig>(&json).unwrap(), homing);
+///
+/// // f32 fields come back exactly
+/// let stepper = StepperConfig::new(3, 12800).with_phase_current(2.83).with_peak_current_scale(13.7);
+/// let json = serde_json::to_string(&stepper).unwrap();
+/// assert_eq!(serde_json::from_str::<StepperConfig>(&json).unwrap(), stepper);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HomingConfig {
     pub input_no: u8,
     pub function: DigitalInputFunction,
@@ -266,10 +876,74 @@ impl Default for HomingConfig {
     }
 }
 
+/// Location of the nine path blocks in the register map
+///
+/// Path `n` starts at `base + n * stride`, with the registers of a block at
+/// the `PATH_*_OFFSET` offsets. The default matches the documented EM2RS map
+/// (`PATH0_BASE`, 8 registers apart); firmware variants spacing the blocks
+/// differently can be handled with [`PathLayout::new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathLayout {
+    base: u16,
+    stride: u16,
+}
+
+impl PathLayout {
+    /// Create a layout, checking that the path blocks don't overlap and fit
+    /// in the register address space
+    pub fn new(base: u16, stride: u16) -> Result<Self> {
+        use crate::registers::PATH_SPECIAL_PARAM_OFFSET;
+        if stride <= PATH_SPECIAL_PARAM_OFFSET {
+            return Err(Em2rsError::InvalidParameter(format!(
+                "path stride {} would overlap path blocks, must be at least {}",
+                stride,
+                PATH_SPECIAL_PARAM_OFFSET + 1
+            )));
+        }
+        let last = u32::from(base) + 8 * u32::from(stride) + u32::from(PATH_SPECIAL_PARAM_OFFSET);
+        if last > u32::from(u16::MAX) {
+            return Err(Em2rsError::InvalidParameter(format!(
+                "path blocks from {:#06x} with stride {} exceed the register address space",
+                base, stride
+            )));
+        }
+        Ok(Self { base, stride })
+    }
+
+    /// First register of path 0
+    pub fn base(&self) -> u16 {
+        self.base
+    }
+
+    /// Distance between the first registers of consecutive paths
+    pub fn stride(&self) -> u16 {
+        self.stride
+    }
+
+    /// First register of a path (0-8), or `None` for an invalid path ID
+    pub fn path_base(&self, path_id: u8) -> Option<u16> {
+        (path_id <= 8).then(|| self.base + u16::from(path_id) * self.stride)
+    }
+}
+
+impl Default for PathLayout {
+    fn default() -> Self {
+        Self {
+            base: crate::registers::PATH0_BASE,
+            stride: crate::registers::PATH1_BASE - crate::registers::PATH0_BASE,
+        }
+    }
+}
+
 /// Path configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PathConfig {
     pub path_id: u8,
+    /// Motion run by the path, `PositionPositioning` by default. The position
+    /// is not written for `VelocityMovement`, which runs at `velocity` until
+    /// stopped.
+    pub motion_type: PathMotionType,
     pub absolute_position: bool,
     pub position: u32,
     pub velocity: u16,
@@ -279,40 +953,313 @@ pub struct PathConfig {
 }
 
 impl PathConfig {
+    /// Default acceleration and deceleration (ms/1000rpm)
+    pub const DEFAULT_RAMP: u16 = 100;
+
+    /// Velocity (RPM) above which a default deceleration is considered suspicious
+    pub const HIGH_VELOCITY_RPM: u16 = 1000;
+
     pub fn new(path_id: u8) -> Result<Self> {
         if path_id > 8 {
             return Err(Em2rsError::InvalidPath(path_id));
         }
         Ok(Self {
             path_id,
+            motion_type: PathMotionType::PositionPositioning,
             absolute_position: true,
             position: 0,
             velocity: 100,
-            acceleration: 100,
-            deceleration: 100,
+            acceleration: Self::DEFAULT_RAMP,
+            deceleration: Self::DEFAULT_RAMP,
             pause_time: 0,
         })
     }
+
+    pub fn with_motion_type(mut self, motion_type: PathMotionType) -> Self {
+        self.motion_type = motion_type;
+        self
+    }
+
+    pub fn with_position(mut self, position: u32) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_velocity(mut self, rpm: u16) -> Self {
+        self.velocity = rpm;
+        self
+    }
+
+    pub fn with_acceleration(mut self, acc: u16) -> Self {
+        self.acceleration = acc;
+        self
+    }
+
+    pub fn with_deceleration(mut self, dec: u16) -> Self {
+        self.deceleration = dec;
+        self
+    }
+
+    pub fn with_pause_time(mut self, ms: u16) -> Self {
+        self.pause_time = ms;
+        self
+    }
+
+    /// Move relative to the current position
+    pub fn relative(mut self) -> Self {
+        self.absolute_position = false;
+        self
+    }
+
+    /// Move to an absolute position, the default
+    pub fn absolute(mut self) -> Self {
+        self.absolute_position = true;
+        self
+    }
+
+    /// Decode a path register block read from the path's base register
+    ///
+    /// Returns `None` if the path's motion type is `NoAction` or unknown.
+    pub(crate) fn from_block(path_id: u8, block: &[u16]) -> Option<Self> {
+        use crate::registers::*;
+        let ctrl = block[PATH_CTRL_OFFSET as usize];
+        let motion_type = PathMotionType::from_bits(ctrl)?;
+        if motion_type == PathMotionType::NoAction {
+            return None;
+        }
+        Some(Self {
+            path_id,
+            motion_type,
+            absolute_position: ctrl & 0x0040 == 0,
+            position: crate::common::join_words(
+                block[PATH_POSITION_H_OFFSET as usize],
+                block[PATH_POSITION_L_OFFSET as usize],
+            ),
+            velocity: block[PATH_VELOCITY_OFFSET as usize],
+            acceleration: block[PATH_ACC_OFFSET as usize],
+            deceleration: block[PATH_DEC_OFFSET as usize],
+            pause_time: block[PATH_PAUSE_TIME_OFFSET as usize],
+        })
+    }
+
+    /// Validate acceleration and deceleration
+    ///
+    /// Both ramps must be non-zero. A deceleration left at [`Self::DEFAULT_RAMP`]
+    /// while the velocity is at least [`Self::HIGH_VELOCITY_RPM`] usually means it
+    /// was forgotten and can cause overshoot: this logs a warning, or returns an
+    /// error when `strict` is set.
+    pub fn check_ramps(&self, strict: bool) -> Result<()> {
+        if self.acceleration == 0 || self.deceleration == 0 {
+            return Err(Em2rsError::InvalidParameter(format!(
+                "path {}: acceleration and deceleration must be non-zero",
+                self.path_id
+            )));
+        }
+        if self.deceleration == Self::DEFAULT_RAMP && self.velocity >= Self::HIGH_VELOCITY_RPM {
+            let msg = format!(
+                "path {}: deceleration left at default {} ms/1000rpm for {} rpm",
+                self.path_id, Self::DEFAULT_RAMP, self.velocity
+            );
+            if strict {
+                return Err(Em2rsError::InvalidParameter(msg));
+            }
+            log::warn!("{msg}");
+        }
+        Ok(())
+    }
+}
+
+/// Soft limit positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftLimits {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Holding brake timing and threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrakeConfig {
+    /// Delay after enabling before the brake is released (ms)
+    pub release_delay: u16,
+    /// Delay after the brake is locked before disabling (ms)
+    pub lock_delay: u16,
+    /// Velocity below which the brake engages (RPM, 0-3000)
+    pub threshold: u16,
+}
+
+/// Repetitive jog cycle, see `start_repetitive_jog`
+///
+/// The jog registers are shared with `jog_motor` and jog inputs, which run
+/// the same cycle once configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepetitiveJogConfig {
+    pub direction: Direction,
+    /// Jog velocity (RPM, 1-3000)
+    pub velocity: u16,
+    /// Pause between runs (`INTERVAL`, ms)
+    pub interval: u16,
+    /// Length of each run (`RUNNING_TIME`, raw drive units)
+    pub running_time: u16,
+    /// Acceleration and deceleration time of each run (`ACC_DEC_TIME`, ms)
+    pub acc_dec_time: u16,
+}
+
+/// Motion command smoothing
+///
+/// Groups the drive's smoothing parameters. The EM2RS only exposes the command
+/// filter time: ramps are set per path through acceleration and deceleration,
+/// and the register map documents no jerk limit or ramp shaping register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotionSmoothing {
+    /// Filter applied to the motion command (`CMD_FILTER_TIME`, raw drive
+    /// units), 0 for no filtering. Higher values soften starts and stops at
+    /// the cost of added lag.
+    pub cmd_filter_time: u16,
+}
+
+/// Motor electrical parameters, for cloning a commissioned drive's tuning
+///
+/// All values are raw register contents, so a set read from one drive can be
+/// written to another unchanged. The motor registers are not contiguous and
+/// the `0x0191`-`0x023D` range also holds RS485 and jog settings, which must
+/// not be copied between drives, so only the registers below are transferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MotorParams {
+    /// `PEAK_CURRENT`
+    pub peak_current: u16,
+    /// `MOTOR_INDUCTANCE`
+    pub inductance: u16,
+    /// `MOTOR_MODEL`
+    pub motor_model: u16,
+    /// `BACK_EMF_COEF`
+    pub back_emf_coef: u16,
+    /// `CURRENT_LOOP_PROPORTIONAL_KP`
+    pub current_loop_proportional_kp: u16,
+    /// `CURRENT_LOOP_KI`
+    pub current_loop_ki: u16,
+    /// `CURRENT_LOOP_KP`
+    pub current_loop_kp: u16,
+    /// `CURRENT_LOOP_KC`
+    pub current_loop_kc: u16,
+}
+
+/// Section of a [`MachineConfig`], reported when applying it fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSection {
+    SoftLimits,
+    Brake,
+    Homing,
+    Path(u8),
+    Stepper,
+    Enable,
+}
+
+impl std::fmt::Display for ConfigSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SoftLimits => write!(f, "soft limits"),
+            Self::Brake => write!(f, "brake"),
+            Self::Homing => write!(f, "homing"),
+            Self::Path(id) => write!(f, "path {id}"),
+            Self::Stepper => write!(f, "stepper"),
+            Self::Enable => write!(f, "enable"),
+        }
+    }
+}
+
+/// Whole-machine configuration applied by `apply_machine_config`
+///
+/// Sections are applied in this order: the stepper parameters written by
+/// `init`, then soft limits, brake, homing and paths, and finally the
+/// stepper's `enable_method`. The stepper section goes first because it sets
+/// `pulse_per_rev`, the unit of every position written afterwards; the
+/// envelope is then in place before any path, and the motor is only enabled
+/// once everything else is written. The drive cannot roll back: on failure the
+/// error names the section that failed and every earlier section has already
+/// been written. The motor is only enabled if every other section succeeded,
+/// and the client keeps its previous `StepperConfig`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineConfig {
+    pub stepper: StepperConfig,
+    pub soft_limits: Option<SoftLimits>,
+    pub brake: Option<BrakeConfig>,
+    pub homing: Option<HomingConfig>,
+    pub paths: Vec<PathConfig>,
+}
+
+impl MachineConfig {
+    pub fn new(stepper: StepperConfig) -> Self {
+        Self {
+            stepper,
+            soft_limits: None,
+            brake: None,
+            homing: None,
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// How `init` enables the motor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EnableMethod {
+    /// Leave the enable state untouched
+    None,
+    /// Force enable through `FORCED_ENA`
+    ForcedEnable,
+    /// Enable through `RS485_CONTROL_WORD`, for drives controlled over RS485
+    /// where `FORCED_ENA` has no effect
+    Rs485ControlWord,
+}
+
+/// 64-bit FNV-1a hash of register addresses and values
+///
+/// Stable across platforms and Rust versions, unlike the std hashers.
+pub(crate) fn fingerprint_registers(registers: impl IntoIterator<Item = (u16, u16)>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (addr, value) in registers {
+        for byte in addr.to_be_bytes().into_iter().chain(value.to_be_bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
 }
 
 /// Stepper motor configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StepperConfig {
     pub slave_id: u8,
+    pub baud_rate: u32,
     pub pulse_per_rev: u16,
     pub direction: Direction,
     pub phase_current: f32,
+    /// Factor converting phase current (A) to the `PEAK_CURRENT` register
+    pub peak_current_scale: f32,
     pub inductance: u16,
+    pub enable_method: EnableMethod,
 }
 
 impl StepperConfig {
+    /// Default peak current scale: peak = phase current * 1.4, in 0.1 A
+    pub const DEFAULT_PEAK_CURRENT_SCALE: f32 = 14.0;
+
     pub fn new(slave_id: u8, pulse_per_rev: u16) -> Self {
         Self {
             slave_id,
+            baud_rate: 9600,
             pulse_per_rev,
             direction: Direction::Clockwise,
             phase_current: 1.0,
+            peak_current_scale: Self::DEFAULT_PEAK_CURRENT_SCALE,
             inductance: 1000,
+            enable_method: EnableMethod::None,
         }
     }
 
@@ -321,6 +1268,11 @@ impl StepperConfig {
         self
     }
 
+    pub fn with_peak_current_scale(mut self, scale: f32) -> Self {
+        self.peak_current_scale = scale;
+        self
+    }
+
     pub fn with_inductance(mut self, inductance: u16) -> Self {
         self.inductance = inductance;
         self
@@ -330,4 +1282,44 @@ impl StepperConfig {
         self.direction = direction;
         self
     }
+
+    pub fn with_enable_method(mut self, enable_method: EnableMethod) -> Self {
+        self.enable_method = enable_method;
+        self
+    }
+
+    /// Serial baud rate of the bus, used to derive the inter-frame delay
+    pub fn with_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+}
+
+/// Split a signed position into its `[high, low]` register words
+///
+/// The drive stores positions as 32-bit two's complement values over two
+/// registers, high word first:
+///
+/// ```
+/// use em2rs::position_words;
+///
+/// assert_eq!(position_words(-5000), [0xFFFF, 0xEC78]);
+/// assert_eq!(position_words(-1), [0xFFFF, 0xFFFF]);
+/// assert_eq!(position_words(i32::MIN), [0x8000, 0x0000]);
+/// assert_eq!(position_words(i32::MAX), [0x7FFF, 0xFFFF]);
+/// ```
+pub fn position_words(position: i32) -> [u16; 2] {
+    crate::common::split_words(position as u32)
+}
+
+/// Recommended silent interval between Modbus RTU frames for a baud rate
+///
+/// Implements the 3.5 character time rule of the Modbus serial line spec,
+/// with 11 bits per character (~4ms at 9600 baud). Above 19200 baud, and for
+/// a baud rate of 0, the fixed 1.75ms interval recommended by the spec is used.
+pub fn recommended_inter_frame_delay(baud: u32) -> Duration {
+    match baud {
+        1..=19200 => Duration::from_micros(38_500_000u64.div_ceil(u64::from(baud))),
+        _ => Duration::from_micros(1750),
+    }
 }
@@ -0,0 +1,115 @@
+//! Modbus TCP against a local server standing in for an RS485 gateway
+//!
+//! The server keeps one register bank per unit ID, like the drives behind a
+//! gateway, so the tests show each client reaching its own drive over a
+//! single shared connection.
+#![cfg(feature = "tcp")]
+
+use std::collections::HashMap;
+use std::future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use em2rs::{registers, Em2rsClient, Em2rsSyncClient, StepperConfig};
+use tokio::net::TcpListener;
+use tokio_modbus::prelude::*;
+use tokio_modbus::server::tcp::{accept_tcp_connection, Server};
+use tokio_modbus::server::Service;
+
+const PATH0_VELOCITY: u16 = registers::PATH0_BASE + registers::PATH_VELOCITY_OFFSET;
+
+/// Drives behind the gateway, as registers keyed by unit ID and address
+#[derive(Clone, Default)]
+struct Gateway {
+    registers: Arc<Mutex<HashMap<(u8, u16), u16>>>,
+}
+
+impl Gateway {
+    fn get(&self, unit: u8, addr: u16) -> Option<u16> {
+        self.registers.lock().unwrap().get(&(unit, addr)).copied()
+    }
+}
+
+impl Service for Gateway {
+    type Request = SlaveRequest<'static>;
+    type Response = Response;
+    type Exception = ExceptionCode;
+    type Future = future::Ready<Result<Response, ExceptionCode>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let mut registers = self.registers.lock().unwrap();
+        let unit = req.slave;
+        let response = match req.request {
+            Request::ReadHoldingRegisters(addr, count) => Ok(Response::ReadHoldingRegisters(
+                (0..count)
+                    .map(|i| registers.get(&(unit, addr + i)).copied().unwrap_or(0))
+                    .collect(),
+            )),
+            Request::WriteSingleRegister(addr, value) => {
+                registers.insert((unit, addr), value);
+                Ok(Response::WriteSingleRegister(addr, value))
+            }
+            Request::WriteMultipleRegisters(addr, values) => {
+                for (i, value) in values.iter().enumerate() {
+                    registers.insert((unit, addr + i as u16), *value);
+                }
+                Ok(Response::WriteMultipleRegisters(addr, values.len() as u16))
+            }
+            _ => Err(ExceptionCode::IllegalFunction),
+        };
+        future::ready(response)
+    }
+}
+
+/// Serve `gateway` on a free localhost port from the current runtime
+async fn start_gateway(gateway: Gateway) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = Server::new(listener);
+    tokio::spawn(async move {
+        let on_connected = |stream, socket_addr| {
+            let gateway = gateway.clone();
+            async move { accept_tcp_connection(stream, socket_addr, |_| Ok(Some(gateway.clone()))) }
+        };
+        server.serve(&on_connected, |err| panic!("gateway error: {err}")).await
+    });
+    addr
+}
+
+#[tokio::test]
+async fn unit_id_follows_each_client() {
+    let gateway = Gateway::default();
+    let addr = start_gateway(gateway.clone()).await;
+    let ctx = tcp::connect_slave(addr, Slave::from(1)).await.unwrap();
+
+    let mut motor1 = Em2rsClient::new(ctx, StepperConfig::new(1, 10000)).with_auto_reselect(true);
+    motor1.init().await.unwrap();
+    motor1.set_path_velocity(0, 300).await.unwrap();
+
+    let ctx = motor1.into_context();
+    let mut motor2 = Em2rsClient::new(ctx, StepperConfig::new(2, 20000)).with_auto_reselect(true);
+    motor2.init().await.unwrap();
+    motor2.set_path_velocity(0, 600).await.unwrap();
+
+    assert_eq!(gateway.get(1, registers::PULSE_PER_REV), Some(10000));
+    assert_eq!(gateway.get(2, registers::PULSE_PER_REV), Some(20000));
+    assert_eq!(gateway.get(1, PATH0_VELOCITY), Some(300));
+    assert_eq!(gateway.get(2, PATH0_VELOCITY), Some(600));
+    assert_eq!(motor2.get_path_velocity(0).await.unwrap(), 600);
+}
+
+#[test]
+fn sync_client_switches_unit_id() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let gateway = Gateway::default();
+    let addr = runtime.block_on(start_gateway(gateway.clone()));
+    let ctx = sync::tcp::connect_slave(addr, Slave::from(3)).unwrap();
+
+    let mut motor3 = Em2rsSyncClient::new(ctx, StepperConfig::new(3, 10000));
+    motor3.set_path_velocity(0, 150).unwrap();
+    motor3.change_slave_id(4).unwrap();
+    motor3.set_path_velocity(0, 250).unwrap();
+
+    assert_eq!(gateway.get(3, registers::RS485_ID), Some(4));
+    assert_eq!(gateway.get(3, PATH0_VELOCITY), Some(150));
+    assert_eq!(gateway.get(4, PATH0_VELOCITY), Some(250));
+}